@@ -2,6 +2,7 @@ mod future;
 pub mod net;
 pub mod runtime;
 pub mod task;
+pub mod time;
 
 use runtime::Handle;
 use task::JoinHandle;
@@ -13,5 +14,5 @@ where
     T: Future + 'static,
     T::Output: 'static,
 {
-    Handle::with_current(|handle| handle.scheduler().spawn(task))
+    Handle::with_current(|handle| handle.spawn(task))
 }
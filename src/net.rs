@@ -1,2 +1,56 @@
-mod tcp;
-pub use tcp::{TcpListener, TcpStream};
+use crate::runtime::io::{Interest, Registration};
+
+use std::io;
+use std::os::unix::io::RawFd;
+use std::task::{Context, Poll};
+use tokio::io::ReadBuf;
+
+mod sys;
+
+pub mod tcp;
+pub use tcp::{TcpListener, TcpSocket, TcpStream};
+
+mod udp;
+pub use udp::UdpSocket;
+
+pub mod unix;
+pub use unix::{UnixDatagram, UnixListener, UnixStream};
+
+/// Read from the stream socket `fd` into the unfilled part of `buf`, which
+/// may be uninitialized.
+pub(crate) fn poll_read_buf(
+    registration: &Registration,
+    fd: RawFd,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+) -> Poll<io::Result<()>> {
+    // `recv` only writes initialized bytes
+    let unfilled = unsafe { buf.unfilled_mut() };
+    let len = unfilled.len();
+
+    match registration.poll_stream_io(cx, Interest::READABLE, len, || sys::recv(fd, unfilled, 0)) {
+        Poll::Ready(Ok(n)) => {
+            unsafe { buf.assume_init(n) };
+            buf.advance(n);
+            Poll::Ready(Ok(()))
+        }
+        Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+        Poll::Pending => Poll::Pending,
+    }
+}
+
+/// Write all of `buf` with `poll_write`, which writes some of the bytes it
+/// is given.
+pub(crate) async fn write_all(
+    mut buf: &[u8],
+    mut poll_write: impl FnMut(&mut Context<'_>, &[u8]) -> Poll<io::Result<usize>>,
+) -> io::Result<()> {
+    while !buf.is_empty() {
+        match crate::future::poll_fn(|cx| poll_write(cx, buf)).await? {
+            0 => return Err(io::ErrorKind::WriteZero.into()),
+            n => buf = &buf[n..],
+        }
+    }
+
+    Ok(())
+}
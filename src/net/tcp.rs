@@ -1,5 +1,16 @@
+//! TCP utility types.
+
 mod listener;
-pub use listener::TcpListener;
+pub use listener::{Incoming, TcpListener};
+
+mod socket;
+pub use socket::TcpSocket;
+
+mod split;
+pub use split::{ReadHalf, WriteHalf};
+
+mod split_owned;
+pub use split_owned::{OwnedReadHalf, OwnedWriteHalf, ReuniteError};
 
 mod stream;
 pub use stream::TcpStream;
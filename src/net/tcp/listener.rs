@@ -1,18 +1,21 @@
 use crate::net::TcpStream;
 use crate::runtime::io::{Interest, Registration};
 use crate::runtime::Handle;
-use std::task::Context;
+use std::fmt;
 use std::io;
 use std::net::SocketAddr;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 pub struct TcpListener {
+    /// Socket registered with the I/O driver
+    registration: Registration,
+
     /// Mio listener
     mio: mio::net::TcpListener,
 
     addr: SocketAddr,
-
-    /// Socket registered with the I/O driver
-    registration: Registration,
 }
 
 impl TcpListener {
@@ -21,12 +24,42 @@ impl TcpListener {
         TcpListener::new(mio, addr)
     }
 
-    fn new(mut mio: mio::net::TcpListener, addr: SocketAddr) -> io::Result<TcpListener> {
+    /// Creates a `TcpListener` from a bound and listening standard library
+    /// listener, switching it to non-blocking mode.
+    ///
+    /// Must be called from within a runtime with the I/O driver enabled.
+    pub fn from_std(listener: std::net::TcpListener) -> io::Result<TcpListener> {
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        TcpListener::new(mio::net::TcpListener::from_std(listener), addr)
+    }
+
+    /// Turns the listener into a standard library listener, deregistering
+    /// it from the runtime.
+    ///
+    /// The returned listener is in non-blocking mode, use `set_nonblocking`
+    /// to change it.
+    pub fn into_std(self) -> io::Result<std::net::TcpListener> {
+        let TcpListener {
+            registration, mio, ..
+        } = self;
+
+        // Deregister before the socket changes hands
+        drop(registration);
+
+        Ok(unsafe { std::net::TcpListener::from_raw_fd(mio.into_raw_fd()) })
+    }
+
+    pub(crate) fn new(mut mio: mio::net::TcpListener, addr: SocketAddr) -> io::Result<TcpListener> {
         Handle::with_current(|handle| {
             let registration = handle
                 .io()
                 .register(&handle, &mut mio, Interest::READABLE)?;
-            Ok(TcpListener { mio, registration, addr })
+            Ok(TcpListener {
+                mio,
+                registration,
+                addr,
+            })
         })
     }
 
@@ -34,14 +67,75 @@ impl TcpListener {
         Ok(self.addr)
     }
 
-    pub fn poll_accept(&self, cx: &mut Context<'_>) -> std::task::Poll<io::Result<(TcpStream, SocketAddr)>> {
-        todo!()
+    /// Polls to accept a new incoming connection.
+    ///
+    /// If no connection is pending, registers the current task to be woken
+    /// once one is. Only the task of the last call is woken.
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(TcpStream, SocketAddr)>> {
+        let (mio, addr) = match self
+            .registration
+            .poll_io(cx, Interest::READABLE, || self.mio.accept())
+        {
+            Poll::Ready(Ok(ret)) => ret,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let stream = TcpStream::new(mio)?;
+        Poll::Ready(Ok((stream, addr)))
+    }
+
+    /// Returns a stream of incoming connections, for accept loops driven by
+    /// `poll_next`.
+    pub fn incoming(&self) -> Incoming<'_> {
+        Incoming { listener: self }
     }
 
     pub async fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
-        let (mio, addr) = self.registration.async_read(|| self.mio.accept()).await?;
+        crate::future::poll_fn(|cx| self.poll_accept(cx)).await
+    }
+}
+
+impl AsRawFd for TcpListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.mio.as_raw_fd()
+    }
+}
+
+impl AsFd for TcpListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // The descriptor is owned by `self.mio`, which lives as long as
+        // `self`
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
+
+impl fmt::Debug for TcpListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.mio.fmt(f)
+    }
+}
+
+/// Stream of the connections accepted by a `TcpListener`.
+///
+/// Created by `TcpListener::incoming`.
+#[derive(Debug)]
+pub struct Incoming<'a> {
+    listener: &'a TcpListener,
+}
 
-        let stream = TcpStream::new(mio, self.addr)?;
-        Ok((stream, addr))
+impl Incoming<'_> {
+    /// Polls for the next accepted connection.
+    ///
+    /// Never returns `None`, as a listener accepts connections until it is
+    /// dropped. Errors accepting a connection do not end the stream.
+    pub fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<io::Result<TcpStream>>> {
+        match self.listener.poll_accept(cx) {
+            Poll::Ready(res) => Poll::Ready(Some(res.map(|(stream, _)| stream))),
+            Poll::Pending => Poll::Pending,
+        }
     }
 }
@@ -0,0 +1,161 @@
+use crate::net::{sys, TcpListener, TcpStream};
+
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::os::unix::io::{AsRawFd, OwnedFd, RawFd};
+
+/// A TCP socket that has not yet been converted to a `TcpStream` or
+/// `TcpListener`.
+///
+/// Used to set socket options before binding, listening or connecting, such
+/// as `SO_REUSEPORT` to share a port between processes.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stokio::net::TcpSocket;
+///
+/// # fn dox() -> std::io::Result<()> {
+/// let socket = TcpSocket::new_v4()?;
+/// socket.set_reuseport(true)?;
+/// socket.bind("127.0.0.1:8080".parse().unwrap())?;
+///
+/// let listener = socket.listen(1024)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TcpSocket {
+    fd: OwnedFd,
+}
+
+impl TcpSocket {
+    /// Creates a new socket configured for IPv4.
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        TcpSocket::new(libc::AF_INET)
+    }
+
+    /// Creates a new socket configured for IPv6.
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        TcpSocket::new(libc::AF_INET6)
+    }
+
+    fn new(domain: libc::c_int) -> io::Result<TcpSocket> {
+        let fd = sys::socket(domain, libc::SOCK_STREAM)?;
+        Ok(TcpSocket { fd })
+    }
+
+    /// Allows the socket to bind to an in-use port, as long as no socket is
+    /// listening on it. Sets `SO_REUSEADDR`.
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.set_bool(libc::SO_REUSEADDR, reuseaddr)
+    }
+
+    /// Retrieves the value of `SO_REUSEADDR`.
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.get_bool(libc::SO_REUSEADDR)
+    }
+
+    /// Allows multiple sockets to bind to the same port, the kernel
+    /// distributing incoming connections between them. Sets `SO_REUSEPORT`.
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.set_bool(libc::SO_REUSEPORT, reuseport)
+    }
+
+    /// Retrieves the value of `SO_REUSEPORT`.
+    pub fn reuseport(&self) -> io::Result<bool> {
+        self.get_bool(libc::SO_REUSEPORT)
+    }
+
+    /// Sets the size of the send buffer. Sets `SO_SNDBUF`.
+    ///
+    /// The OS may adjust the value, see `send_buffer_size`.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.set_size(libc::SO_SNDBUF, size)
+    }
+
+    /// Returns the size of the send buffer. Retrieves `SO_SNDBUF`.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        self.get_size(libc::SO_SNDBUF)
+    }
+
+    /// Sets the size of the receive buffer. Sets `SO_RCVBUF`.
+    ///
+    /// The OS may adjust the value, see `recv_buffer_size`.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.set_size(libc::SO_RCVBUF, size)
+    }
+
+    /// Returns the size of the receive buffer. Retrieves `SO_RCVBUF`.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        self.get_size(libc::SO_RCVBUF)
+    }
+
+    /// Returns the address the socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        sys::local_addr(self.fd.as_raw_fd())
+    }
+
+    /// Binds the socket to `addr`.
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        sys::bind(self.fd.as_raw_fd(), &addr)
+    }
+
+    /// Establishes a TCP connection with a peer at `addr`.
+    ///
+    /// The connection is established without blocking the thread.
+    pub async fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        sys::connect(self.fd.as_raw_fd(), &addr)?;
+
+        let mio = mio::net::TcpStream::from_std(std::net::TcpStream::from(self.fd));
+        TcpStream::connect_mio(mio).await
+    }
+
+    /// Converts the socket into a `TcpListener`, queueing up to `backlog`
+    /// connections not yet accepted.
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        sys::listen(self.fd.as_raw_fd(), backlog)?;
+
+        let addr = self.local_addr()?;
+        let mio = mio::net::TcpListener::from_std(std::net::TcpListener::from(self.fd));
+        TcpListener::new(mio, addr)
+    }
+
+    fn set_bool(&self, name: libc::c_int, value: bool) -> io::Result<()> {
+        sys::setsockopt(
+            self.fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            name,
+            value as libc::c_int,
+        )
+    }
+
+    fn get_bool(&self, name: libc::c_int) -> io::Result<bool> {
+        let value: libc::c_int = sys::getsockopt(self.fd.as_raw_fd(), libc::SOL_SOCKET, name)?;
+        Ok(value != 0)
+    }
+
+    fn set_size(&self, name: libc::c_int, size: u32) -> io::Result<()> {
+        let size = size.min(libc::c_int::MAX as u32) as libc::c_int;
+        sys::setsockopt(self.fd.as_raw_fd(), libc::SOL_SOCKET, name, size)
+    }
+
+    fn get_size(&self, name: libc::c_int) -> io::Result<u32> {
+        let size: libc::c_int = sys::getsockopt(self.fd.as_raw_fd(), libc::SOL_SOCKET, name)?;
+        Ok(size as u32)
+    }
+}
+
+impl AsRawFd for TcpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcpSocket")
+            .field("fd", &self.fd.as_raw_fd())
+            .finish()
+    }
+}
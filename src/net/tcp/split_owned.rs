@@ -0,0 +1,186 @@
+use crate::net::TcpStream;
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, IoSlice};
+use std::net::{Shutdown, SocketAddr};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Owned read half of a `TcpStream`, created by `TcpStream::into_split`.
+#[derive(Debug)]
+pub struct OwnedReadHalf {
+    inner: Rc<TcpStream>,
+}
+
+/// Owned write half of a `TcpStream`, created by `TcpStream::into_split`.
+///
+/// Dropping the write half shuts down the write direction of the stream,
+/// unless it is put back together with its read half.
+#[derive(Debug)]
+pub struct OwnedWriteHalf {
+    inner: Rc<TcpStream>,
+    shutdown_on_drop: bool,
+}
+
+/// Error returned by `OwnedReadHalf::reunite` when the halves come from
+/// different streams, giving them back.
+#[derive(Debug)]
+pub struct ReuniteError(pub OwnedReadHalf, pub OwnedWriteHalf);
+
+pub(crate) fn split_owned(stream: TcpStream) -> (OwnedReadHalf, OwnedWriteHalf) {
+    // The runtime is single-threaded, the halves share the stream through
+    // an `Rc`.
+    let inner = Rc::new(stream);
+
+    let read = OwnedReadHalf {
+        inner: inner.clone(),
+    };
+    let write = OwnedWriteHalf {
+        inner,
+        shutdown_on_drop: true,
+    };
+
+    (read, write)
+}
+
+impl OwnedReadHalf {
+    /// Puts the halves back together into the original `TcpStream`.
+    ///
+    /// Fails if `other` is not the write half split from the same stream.
+    pub fn reunite(self, other: OwnedWriteHalf) -> Result<TcpStream, ReuniteError> {
+        reunite(self, other)
+    }
+
+    /// Reads some bytes into `buf`, returning how many were read.
+    pub async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        crate::future::poll_fn(|cx| self.inner.poll_read_inner(cx, buf)).await
+    }
+
+    /// Returns the remote address of the stream.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    /// Returns the local address of the stream.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+}
+
+impl OwnedWriteHalf {
+    /// Puts the halves back together into the original `TcpStream`.
+    ///
+    /// Fails if `other` is not the read half split from the same stream.
+    pub fn reunite(self, other: OwnedReadHalf) -> Result<TcpStream, ReuniteError> {
+        reunite(other, self)
+    }
+
+    /// Writes some bytes from `buf`, returning how many were written.
+    pub async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        crate::future::poll_fn(|cx| self.inner.poll_write_inner(cx, buf)).await
+    }
+
+    /// Writes all of `buf`.
+    pub async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        crate::net::write_all(buf, |cx, buf| self.inner.poll_write_inner(cx, buf)).await
+    }
+
+    /// Returns the remote address of the stream.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+
+    /// Returns the local address of the stream.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+}
+
+fn reunite(read: OwnedReadHalf, mut write: OwnedWriteHalf) -> Result<TcpStream, ReuniteError> {
+    if !Rc::ptr_eq(&read.inner, &write.inner) {
+        return Err(ReuniteError(read, write));
+    }
+
+    write.shutdown_on_drop = false;
+    drop(write);
+
+    // The read half now holds the only reference
+    Ok(Rc::try_unwrap(read.inner).expect("TcpStream: try_unwrap failed in reunite"))
+}
+
+impl Drop for OwnedWriteHalf {
+    fn drop(&mut self) {
+        if self.shutdown_on_drop {
+            // Nothing to report the error to, the connection may already be
+            // closed.
+            let _ = self.inner.shutdown(Shutdown::Write);
+        }
+    }
+}
+
+impl AsyncRead for OwnedReadHalf {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        self.inner.poll_read_buf(cx, buf)
+    }
+}
+
+impl AsyncWrite for OwnedWriteHalf {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_write_inner(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.inner.poll_write_vectored_inner(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // tcp stream is always flushed
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.inner.poll_shutdown_inner()
+    }
+}
+
+impl AsRef<TcpStream> for OwnedReadHalf {
+    fn as_ref(&self) -> &TcpStream {
+        &self.inner
+    }
+}
+
+impl AsRef<TcpStream> for OwnedWriteHalf {
+    fn as_ref(&self) -> &TcpStream {
+        &self.inner
+    }
+}
+
+impl fmt::Display for ReuniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tried to reunite halves that are not from the same socket"
+        )
+    }
+}
+
+impl Error for ReuniteError {}
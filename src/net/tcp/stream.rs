@@ -1,163 +1,532 @@
+use crate::net::sys;
+use crate::net::tcp::split::{split, ReadHalf, WriteHalf};
+use crate::net::tcp::split_owned::{split_owned, OwnedReadHalf, OwnedWriteHalf};
 use crate::runtime::io::{Interest, Ready, Registration};
 use crate::runtime::Handle;
 
 use std::fmt;
-use std::io::{self, Read, Write};
-use std::net::SocketAddr;
-use std::task::{self, Poll};
+use std::io::{self, IoSlice, IoSliceMut, Read};
+use std::net::{Shutdown, SocketAddr, ToSocketAddrs};
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, RawFd};
 use std::pin::Pin;
-use std::os::unix::io::RawFd;
-use std::os::unix::io::AsRawFd;
+use std::task::{self, Poll};
+use std::time::Duration;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
 pub struct TcpStream {
-    mio: mio::net::TcpStream,
-
-    addr: SocketAddr,
-
     /// Socket registered with the I/O driver
     registration: Registration,
-}
 
+    mio: mio::net::TcpStream,
+}
 
 impl fmt::Debug for TcpStream {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("TcpStream")
-         .finish()
+        f.debug_struct("TcpStream").finish()
     }
 }
 
 impl TcpStream {
-    pub(crate) fn new(mut mio: mio::net::TcpStream, addr: SocketAddr) -> io::Result<TcpStream> {
+    /// Opens a TCP connection to a remote host.
+    ///
+    /// The connection is established without blocking the thread.
+    pub async fn connect(addr: SocketAddr) -> io::Result<TcpStream> {
+        let mio = mio::net::TcpStream::connect(addr)?;
+        TcpStream::connect_mio(mio).await
+    }
+
+    /// Register a socket on which a non-blocking connect was started and
+    /// wait for the connection to be established.
+    pub(crate) async fn connect_mio(mio: mio::net::TcpStream) -> io::Result<TcpStream> {
+        let stream = TcpStream::new(mio)?;
+
+        // The socket becomes writable once the connection is established or
+        // has failed.
+        loop {
+            stream.registration.write_ready().await;
+
+            if let Some(e) = stream.mio.take_error()? {
+                return Err(e);
+            }
+
+            match stream.mio.peer_addr() {
+                Ok(_) => return Ok(stream),
+                // Spurious wake up, the connection is still in progress
+                Err(ref e) if e.kind() == io::ErrorKind::NotConnected => {
+                    stream.registration.clear_readiness(Ready::WRITABLE);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Opens a TCP connection to the first address of `addrs` accepting it.
+    ///
+    /// Addresses are tried in order and the error of the last attempt is
+    /// returned if all fail. Resolving host names blocks the thread.
+    pub async fn connect_addrs(addrs: impl ToSocketAddrs) -> io::Result<TcpStream> {
+        let mut last_err = None;
+
+        for addr in addrs.to_socket_addrs()? {
+            match TcpStream::connect(addr).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve to any address",
+            )
+        }))
+    }
+
+    /// Opens a TCP connection to a remote host, failing with
+    /// `io::ErrorKind::TimedOut` if it is not established within `timeout`.
+    ///
+    /// Requires the time driver to be enabled.
+    pub async fn connect_timeout(addr: SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        crate::time::timeout(timeout, TcpStream::connect(addr)).await?
+    }
+
+    /// Creates a `TcpStream` from a connected standard library stream,
+    /// switching it to non-blocking mode.
+    ///
+    /// Must be called from within a runtime with the I/O driver enabled.
+    pub fn from_std(stream: std::net::TcpStream) -> io::Result<TcpStream> {
+        stream.set_nonblocking(true)?;
+        TcpStream::new(mio::net::TcpStream::from_std(stream))
+    }
+
+    /// Turns the stream into a standard library stream, deregistering it
+    /// from the runtime.
+    ///
+    /// The returned stream is in non-blocking mode, use `set_nonblocking` to
+    /// change it.
+    pub fn into_std(self) -> io::Result<std::net::TcpStream> {
+        let TcpStream { registration, mio } = self;
+
+        // Deregister before the socket changes hands
+        drop(registration);
+
+        Ok(unsafe { std::net::TcpStream::from_raw_fd(mio.into_raw_fd()) })
+    }
+
+    pub(crate) fn new(mut mio: mio::net::TcpStream) -> io::Result<TcpStream> {
         Handle::with_current(|handle| {
-            let registration =
-                handle
-                    .io()
-                    .register(&handle, &mut mio, Interest::READABLE)?;
-            Ok(TcpStream { mio, registration, addr })
+            let registration = handle.io().register(
+                handle,
+                &mut mio,
+                Interest::READABLE.add(Interest::WRITABLE),
+            )?;
+            Ok(TcpStream { mio, registration })
         })
     }
 
-    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
-        Ok(self.addr)
+    /// Returns the local address of the connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.local_addr()
+    }
+
+    /// Returns the remote address of the connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.peer_addr()
+    }
+
+    /// Splits the stream into a read half and a write half borrowing it,
+    /// which can be used concurrently, e.g. by `join`ed futures.
+    pub fn split(&mut self) -> (ReadHalf<'_>, WriteHalf<'_>) {
+        split(self)
+    }
+
+    /// Splits the stream into a read half and a write half that can be moved
+    /// into separate tasks.
+    ///
+    /// Dropping the write half shuts down the write direction of the
+    /// stream. The halves can be put back together with `reunite`.
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        split_owned(self)
     }
 
+    /// Shuts down the read, write, or both halves of the connection.
+    ///
+    /// Shutting down the write half sends a FIN: the peer reads EOF once it
+    /// has received the data written so far, while this side can keep
+    /// reading.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.mio.shutdown(how)
+    }
+
+    /// Disables Nagle's algorithm, sending small writes right away. Sets
+    /// `TCP_NODELAY`.
     pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
         self.mio.set_nodelay(nodelay)
     }
 
+    /// Retrieves the value of `TCP_NODELAY`.
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.mio.nodelay()
+    }
+
+    /// Sets the time-to-live of outgoing IP packets. Sets `IP_TTL`.
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.mio.set_ttl(ttl)
+    }
+
+    /// Retrieves the value of `IP_TTL`.
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.mio.ttl()
+    }
+
+    /// Sets how long closing the socket waits for unsent data to be
+    /// delivered. Sets `SO_LINGER`.
+    ///
+    /// With `Some(Duration::ZERO)`, the connection is reset on close. With
+    /// `None`, the default, unsent data is delivered in the background.
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        let linger = libc::linger {
+            l_onoff: linger.is_some() as libc::c_int,
+            l_linger: linger.map_or(0, duration_secs),
+        };
+        sys::setsockopt(self.as_raw_fd(), libc::SOL_SOCKET, libc::SO_LINGER, linger)
+    }
+
+    /// Retrieves the value of `SO_LINGER`.
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        let linger: libc::linger =
+            sys::getsockopt(self.as_raw_fd(), libc::SOL_SOCKET, libc::SO_LINGER)?;
+
+        Ok((linger.l_onoff != 0).then(|| Duration::from_secs(linger.l_linger as u64)))
+    }
+
+    /// Enables sending keepalive probes on an idle connection, to detect a
+    /// peer that went away. Sets `SO_KEEPALIVE`.
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        self.set_int(
+            libc::SOL_SOCKET,
+            libc::SO_KEEPALIVE,
+            keepalive as libc::c_int,
+        )
+    }
+
+    /// Retrieves the value of `SO_KEEPALIVE`.
+    pub fn keepalive(&self) -> io::Result<bool> {
+        Ok(self.get_int(libc::SOL_SOCKET, libc::SO_KEEPALIVE)? != 0)
+    }
+
+    /// Sets how long the connection stays idle before the first keepalive
+    /// probe is sent, in whole seconds. Sets `TCP_KEEPIDLE`.
+    pub fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        self.set_int(libc::IPPROTO_TCP, TCP_KEEPIDLE, duration_secs(idle))
+    }
+
+    /// Retrieves the value of `TCP_KEEPIDLE`.
+    pub fn keepalive_idle(&self) -> io::Result<Duration> {
+        let secs = self.get_int(libc::IPPROTO_TCP, TCP_KEEPIDLE)?;
+        Ok(Duration::from_secs(secs as u64))
+    }
+
+    /// Sets the time between keepalive probes, in whole seconds. Sets
+    /// `TCP_KEEPINTVL`.
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        self.set_int(
+            libc::IPPROTO_TCP,
+            libc::TCP_KEEPINTVL,
+            duration_secs(interval),
+        )
+    }
+
+    /// Retrieves the value of `TCP_KEEPINTVL`.
+    pub fn keepalive_interval(&self) -> io::Result<Duration> {
+        let secs = self.get_int(libc::IPPROTO_TCP, libc::TCP_KEEPINTVL)?;
+        Ok(Duration::from_secs(secs as u64))
+    }
+
+    /// Sets the number of unanswered keepalive probes after which the
+    /// connection is dropped. Sets `TCP_KEEPCNT`.
+    pub fn set_keepalive_retries(&self, retries: u32) -> io::Result<()> {
+        let retries = retries.min(libc::c_int::MAX as u32) as libc::c_int;
+        self.set_int(libc::IPPROTO_TCP, libc::TCP_KEEPCNT, retries)
+    }
+
+    /// Retrieves the value of `TCP_KEEPCNT`.
+    pub fn keepalive_retries(&self) -> io::Result<u32> {
+        Ok(self.get_int(libc::IPPROTO_TCP, libc::TCP_KEEPCNT)? as u32)
+    }
+
+    /// Sends ACKs right away rather than delaying them. Sets
+    /// `TCP_QUICKACK`.
+    ///
+    /// The kernel may reset the option, e.g. after the connection goes
+    /// idle, so it is usually set again after each read.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
+        self.set_int(
+            libc::IPPROTO_TCP,
+            libc::TCP_QUICKACK,
+            quickack as libc::c_int,
+        )
+    }
+
+    /// Retrieves the value of `TCP_QUICKACK`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn quickack(&self) -> io::Result<bool> {
+        Ok(self.get_int(libc::IPPROTO_TCP, libc::TCP_QUICKACK)? != 0)
+    }
+
+    /// Sets the size of the send buffer. Sets `SO_SNDBUF`.
+    ///
+    /// The OS may adjust the value, see `send_buffer_size`.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        let size = size.min(libc::c_int::MAX as u32) as libc::c_int;
+        self.set_int(libc::SOL_SOCKET, libc::SO_SNDBUF, size)
+    }
+
+    /// Returns the size of the send buffer. Retrieves `SO_SNDBUF`.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        Ok(self.get_int(libc::SOL_SOCKET, libc::SO_SNDBUF)? as u32)
+    }
+
+    /// Sets the size of the receive buffer. Sets `SO_RCVBUF`.
+    ///
+    /// The OS may adjust the value, see `recv_buffer_size`.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        let size = size.min(libc::c_int::MAX as u32) as libc::c_int;
+        self.set_int(libc::SOL_SOCKET, libc::SO_RCVBUF, size)
+    }
+
+    /// Returns the size of the receive buffer. Retrieves `SO_RCVBUF`.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        Ok(self.get_int(libc::SOL_SOCKET, libc::SO_RCVBUF)? as u32)
+    }
+
+    /// Retrieves and clears the pending socket error, `SO_ERROR`.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.mio.take_error()
+    }
+
     pub async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         crate::future::poll_fn(|cx| self.poll_read_inner(cx, buf)).await
     }
 
-    pub fn poll_peek(&mut self, cx: &mut task::Context<'_>, buf: &mut tokio::io::ReadBuf<'_>) -> Poll<io::Result<usize>> {
-        todo!()
+    /// Reads into `bufs` in a single call, filling them in order.
+    pub async fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+
+        crate::future::poll_fn(|cx| {
+            self.registration
+                .poll_stream_io(cx, Interest::READABLE, len, || {
+                    (&self.mio).read_vectored(bufs)
+                })
+        })
+        .await
     }
 
-    fn poll_read_inner(&mut self, cx: &mut task::Context<'_>, buf: &mut [u8])
-        -> Poll<io::Result<usize>>
-    {
-        loop {
-            let ready = match self.registration.poll_read_ready(cx) {
-                Poll::Ready(ready) => ready,
-                Poll::Pending => return Poll::Pending,
-            };
-
-            if ready.is_read_closed() {
-                // Nothing to read, just return.
-                return Poll::Ready(Ok(0));
-            }
+    /// Tries to read some bytes into `buf` without waiting.
+    ///
+    /// Fails with `io::ErrorKind::WouldBlock` if no data is available.
+    /// Returns 0 at EOF.
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.registration
+            .try_stream_io(Interest::READABLE, buf.len(), || (&self.mio).read(buf))
+    }
 
-            match self.mio.read(buf) {
-                Ok(0) => return Poll::Ready(Ok(0)),
-                Ok(n) if n == buf.len() => return Poll::Ready(Ok(n)),
-                Ok(n) => {
-                    // Partial read indicates the socket buffer has been drained
-                    // Clear readiness, but return anyway
-                    self.registration.clear_readiness(Ready::READABLE);
-                    return Poll::Ready(Ok(n));
-                }
-                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                    self.registration.clear_readiness(Ready::READABLE);
-                }
-                x => return Poll::Ready(x),
+    /// Tries to read into `bufs` in a single call without waiting.
+    ///
+    /// Fails with `io::ErrorKind::WouldBlock` if no data is available.
+    pub fn try_read_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.registration
+            .try_stream_io(Interest::READABLE, len, || (&self.mio).read_vectored(bufs))
+    }
+
+    /// Receives data without removing it from the socket's receive queue,
+    /// so the next read returns it again.
+    ///
+    /// Waits for data if none is available. Returns 0 at EOF.
+    pub async fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut buf = ReadBuf::new(buf);
+        crate::future::poll_fn(|cx| self.poll_peek(cx, &mut buf)).await
+    }
+
+    /// Polls to receive data without removing it from the socket's receive
+    /// queue, filling `buf`.
+    ///
+    /// If no data is available, registers the current task to be woken once
+    /// the socket is readable.
+    pub fn poll_peek(
+        &mut self,
+        cx: &mut task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<usize>> {
+        let fd = self.mio.as_raw_fd();
+        // `recv` only writes initialized bytes
+        let unfilled = unsafe { buf.unfilled_mut() };
+
+        // Unlike reads, a short peek leaves the data queued, so the read
+        // readiness is only cleared once the call would block
+        match self.registration.poll_io(cx, Interest::READABLE, || {
+            sys::recv(fd, unfilled, libc::MSG_PEEK)
+        }) {
+            Poll::Ready(Ok(n)) => {
+                unsafe { buf.assume_init(n) };
+                buf.advance(n);
+                Poll::Ready(Ok(n))
             }
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+            Poll::Pending => Poll::Pending,
         }
     }
 
+    pub(crate) fn poll_read_inner(
+        &self,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        // Data received before the peer closed its half is read before EOF
+        self.registration
+            .poll_stream_io(cx, Interest::READABLE, buf.len(), || (&self.mio).read(buf))
+    }
+
+    /// Writes some bytes from `buf`, waiting for the socket to become
+    /// writable if its send buffer is full.
     pub async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.write_inner(buf)
+        crate::future::poll_fn(|cx| self.poll_write_inner(cx, buf)).await
+    }
+
+    /// Writes from `bufs` in a single call, in order, waiting for the socket
+    /// to become writable if its send buffer is full.
+    pub async fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        crate::future::poll_fn(|cx| self.poll_write_vectored_inner(cx, bufs)).await
+    }
 
-        /*
+    /// Tries to write some bytes from `buf` without waiting.
+    ///
+    /// Fails with `io::ErrorKind::WouldBlock` if the send buffer is full.
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
         self.registration
-            .async_write(|| {
-                self.write_inner(buf)
+            .try_stream_io(Interest::WRITABLE, buf.len(), || {
+                sys::send(self.mio.as_raw_fd(), buf)
             })
-            .await
-            */
-    }
-
-    fn write_inner(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let n = unsafe {
-            libc::send(
-                self.mio.as_raw_fd(),
-                buf.as_ptr() as _,
-                buf.len(),
-                #[cfg(os = "linux")]
-                libc::MSG_NOSIGNAL,
-                #[cfg(not(os = "linux"))]
-                0,
-            )
-        } as usize;
-        Ok(n)
     }
 
-    pub async fn write_all(&mut self, mut buf: &[u8]) -> io::Result<()> {
-        while !buf.is_empty() {
-            match self.write(buf).await {
-                Ok(0) => {
-                    return Err(io::ErrorKind::WriteZero.into());
-                }
-                Ok(n) => buf = &buf[n..],
-                Err(e) => return Err(e),
-            }
-        }
+    /// Tries to write from `bufs` in a single call without waiting.
+    ///
+    /// Fails with `io::ErrorKind::WouldBlock` if the send buffer is full.
+    pub fn try_write_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.registration
+            .try_stream_io(Interest::WRITABLE, len, || {
+                sys::send_vectored(self.mio.as_raw_fd(), bufs)
+            })
+    }
 
-        Ok(())
+    pub(crate) fn poll_write_inner(
+        &self,
+        cx: &mut task::Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.registration
+            .poll_stream_io(cx, Interest::WRITABLE, buf.len(), || {
+                sys::send(self.mio.as_raw_fd(), buf)
+            })
+    }
+
+    pub(crate) fn poll_write_vectored_inner(
+        &self,
+        cx: &mut task::Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.registration
+            .poll_stream_io(cx, Interest::WRITABLE, len, || {
+                sys::send_vectored(self.mio.as_raw_fd(), bufs)
+            })
+    }
+
+    pub async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        crate::net::write_all(buf, |cx, buf| self.poll_write_inner(cx, buf)).await
     }
 }
 
+impl TcpStream {
+    pub(crate) fn poll_read_buf(
+        &self,
+        cx: &mut task::Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        crate::net::poll_read_buf(&self.registration, self.mio.as_raw_fd(), cx, buf)
+    }
+
+    fn set_int(&self, level: libc::c_int, name: libc::c_int, value: libc::c_int) -> io::Result<()> {
+        sys::setsockopt(self.as_raw_fd(), level, name, value)
+    }
+
+    fn get_int(&self, level: libc::c_int, name: libc::c_int) -> io::Result<libc::c_int> {
+        sys::getsockopt(self.as_raw_fd(), level, name)
+    }
+
+    pub(crate) fn poll_shutdown_inner(&self) -> Poll<io::Result<()>> {
+        // Nothing is buffered, the FIN is sent right away
+        Poll::Ready(self.shutdown(Shutdown::Write))
+    }
+}
+
+/// Apple platforms name `TCP_KEEPIDLE` after the `SO_KEEPALIVE` option
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const TCP_KEEPIDLE: libc::c_int = libc::TCP_KEEPALIVE;
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+const TCP_KEEPIDLE: libc::c_int = libc::TCP_KEEPIDLE;
+
+/// Socket options measured in seconds take an `int`
+fn duration_secs(duration: Duration) -> libc::c_int {
+    duration.as_secs().min(libc::c_int::MAX as u64) as libc::c_int
+}
+
 impl AsRawFd for TcpStream {
     fn as_raw_fd(&self) -> RawFd {
-        todo!()
+        self.mio.as_raw_fd()
+    }
+}
+
+impl AsFd for TcpStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // The descriptor is owned by `self.mio`, which lives as long as
+        // `self`
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
     }
 }
 
 impl AsyncRead for TcpStream {
     fn poll_read(
-        mut self: Pin<&mut Self>,
+        self: Pin<&mut Self>,
         cx: &mut task::Context<'_>,
         buf: &mut ReadBuf<'_>,
     ) -> Poll<io::Result<()>> {
-        // TODO: use the non-initializing method
-        match self.poll_read_inner(cx, buf.initialize_unfilled()) {
-            Poll::Ready(Ok(n)) => {
-                buf.advance(n);
-                Poll::Ready(Ok(()))
-            },
-            Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
-            Poll::Pending => Poll::Pending,
-        }
+        self.poll_read_buf(cx, buf)
     }
 }
 
 impl AsyncWrite for TcpStream {
     fn poll_write(
-        mut self: Pin<&mut Self>,
-        _: &mut task::Context<'_>,
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
         buf: &[u8],
     ) -> Poll<io::Result<usize>> {
-        Poll::Ready(self.write_inner(buf))
+        self.poll_write_inner(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_vectored_inner(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
     }
 
     fn poll_flush(self: Pin<&mut Self>, _: &mut task::Context<'_>) -> Poll<io::Result<()>> {
@@ -166,7 +535,6 @@ impl AsyncWrite for TcpStream {
     }
 
     fn poll_shutdown(self: Pin<&mut Self>, _: &mut task::Context<'_>) -> Poll<io::Result<()>> {
-        // tcp stream is always done
-        Poll::Ready(Ok(()))
+        self.poll_shutdown_inner()
     }
 }
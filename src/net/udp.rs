@@ -0,0 +1,276 @@
+use crate::net::sys;
+use crate::runtime::io::{Interest, Registration};
+use crate::runtime::Handle;
+
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::task::{Context, Poll};
+use tokio::io::ReadBuf;
+
+/// A UDP socket.
+///
+/// Without `connect`, datagrams can be sent to and received from any
+/// address with `send_to` and `recv_from`. Once connected, `send` and
+/// `recv` exchange datagrams with the connected address only.
+///
+/// # Examples
+///
+/// ```no_run
+/// use stokio::net::UdpSocket;
+///
+/// # async fn dox() -> std::io::Result<()> {
+/// let socket = UdpSocket::bind("127.0.0.1:0".parse().unwrap())?;
+/// socket.send_to(b"gauge:1|g", "127.0.0.1:8125".parse().unwrap()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct UdpSocket {
+    /// Socket registered with the I/O driver
+    registration: Registration,
+
+    /// Mio socket
+    mio: mio::net::UdpSocket,
+}
+
+impl UdpSocket {
+    /// Creates a UDP socket bound to `addr`.
+    pub fn bind(addr: SocketAddr) -> io::Result<UdpSocket> {
+        let mio = mio::net::UdpSocket::bind(addr)?;
+        UdpSocket::new(mio)
+    }
+
+    fn new(mut mio: mio::net::UdpSocket) -> io::Result<UdpSocket> {
+        Handle::with_current(|handle| {
+            let registration = handle.io().register(
+                handle,
+                &mut mio,
+                Interest::READABLE.add(Interest::WRITABLE),
+            )?;
+            Ok(UdpSocket { registration, mio })
+        })
+    }
+
+    /// Connects the socket to `addr`, so `send` and `recv` may be used and
+    /// datagrams from other addresses are discarded.
+    pub fn connect(&self, addr: SocketAddr) -> io::Result<()> {
+        self.mio.connect(addr)
+    }
+
+    /// Returns the address the socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.local_addr()
+    }
+
+    /// Returns the address the socket is connected to.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.peer_addr()
+    }
+
+    /// Waits for the socket to become readable.
+    ///
+    /// May complete spuriously, a subsequent receive then fails with
+    /// `io::ErrorKind::WouldBlock`.
+    pub async fn readable(&self) -> io::Result<()> {
+        self.registration.read_ready().await;
+        Ok(())
+    }
+
+    /// Waits for the socket to become writable.
+    ///
+    /// May complete spuriously, a subsequent send then fails with
+    /// `io::ErrorKind::WouldBlock`.
+    pub async fn writable(&self) -> io::Result<()> {
+        self.registration.write_ready().await;
+        Ok(())
+    }
+
+    /// Polls for the socket to become readable.
+    pub fn poll_recv_ready(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.registration.poll_read_ready(cx).map(|_| Ok(()))
+    }
+
+    /// Polls for the socket to become writable.
+    pub fn poll_send_ready(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.registration.poll_write_ready(cx).map(|_| Ok(()))
+    }
+
+    /// Sends a datagram to the connected address, returning the number of
+    /// bytes sent.
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.registration
+            .async_io(Interest::WRITABLE, || self.mio.send(buf))
+            .await
+    }
+
+    /// Receives a datagram from the connected address, returning the number
+    /// of bytes read.
+    ///
+    /// Excess bytes of a datagram larger than `buf` are discarded.
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.registration
+            .async_io(Interest::READABLE, || self.mio.recv(buf))
+            .await
+    }
+
+    /// Sends a datagram to `target`, returning the number of bytes sent.
+    pub async fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.registration
+            .async_io(Interest::WRITABLE, || self.mio.send_to(buf, target))
+            .await
+    }
+
+    /// Receives a datagram, returning the number of bytes read and the
+    /// address it came from.
+    ///
+    /// Excess bytes of a datagram larger than `buf` are discarded.
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.registration
+            .async_io(Interest::READABLE, || self.mio.recv_from(buf))
+            .await
+    }
+
+    /// Receives a datagram without removing it from the queue, returning the
+    /// number of bytes read and the address it came from.
+    pub async fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.registration
+            .async_io(Interest::READABLE, || self.mio.peek_from(buf))
+            .await
+    }
+
+    /// Attempts to send a datagram to `target`.
+    ///
+    /// Registers the current task to be woken once the socket is writable if
+    /// it is not.
+    pub fn poll_send_to(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        target: SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        self.registration
+            .poll_io(cx, Interest::WRITABLE, || self.mio.send_to(buf, target))
+    }
+
+    /// Attempts to receive a datagram into `buf`, returning the address it
+    /// came from.
+    ///
+    /// Registers the current task to be woken once the socket is readable if
+    /// it is not.
+    pub fn poll_recv_from(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<SocketAddr>> {
+        let fd = self.mio.as_raw_fd();
+        // `recvfrom` only writes initialized bytes
+        let unfilled = unsafe { buf.unfilled_mut() };
+
+        let (n, addr) = match self
+            .registration
+            .poll_io(cx, Interest::READABLE, || sys::recv_from(fd, unfilled, 0))
+        {
+            Poll::Ready(Ok(ret)) => ret,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        unsafe { buf.assume_init(n) };
+        buf.advance(n);
+        Poll::Ready(Ok(addr))
+    }
+
+    /// Sets `SO_BROADCAST`, allowing datagrams to be sent to a broadcast
+    /// address.
+    pub fn set_broadcast(&self, on: bool) -> io::Result<()> {
+        self.mio.set_broadcast(on)
+    }
+
+    /// Gets the value of `SO_BROADCAST`.
+    pub fn broadcast(&self) -> io::Result<bool> {
+        self.mio.broadcast()
+    }
+
+    /// Sets `IP_MULTICAST_LOOP`, whether multicast datagrams sent are looped
+    /// back to the local socket.
+    pub fn set_multicast_loop_v4(&self, on: bool) -> io::Result<()> {
+        self.mio.set_multicast_loop_v4(on)
+    }
+
+    /// Gets the value of `IP_MULTICAST_LOOP`.
+    pub fn multicast_loop_v4(&self) -> io::Result<bool> {
+        self.mio.multicast_loop_v4()
+    }
+
+    /// Sets `IP_MULTICAST_TTL`, the time-to-live of multicast datagrams
+    /// sent.
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> io::Result<()> {
+        self.mio.set_multicast_ttl_v4(ttl)
+    }
+
+    /// Gets the value of `IP_MULTICAST_TTL`.
+    pub fn multicast_ttl_v4(&self) -> io::Result<u32> {
+        self.mio.multicast_ttl_v4()
+    }
+
+    /// Sets `IPV6_MULTICAST_LOOP`, whether multicast datagrams sent are
+    /// looped back to the local socket.
+    pub fn set_multicast_loop_v6(&self, on: bool) -> io::Result<()> {
+        self.mio.set_multicast_loop_v6(on)
+    }
+
+    /// Gets the value of `IPV6_MULTICAST_LOOP`.
+    pub fn multicast_loop_v6(&self) -> io::Result<bool> {
+        self.mio.multicast_loop_v6()
+    }
+
+    /// Sets `IP_TTL`, the time-to-live of datagrams sent.
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.mio.set_ttl(ttl)
+    }
+
+    /// Gets the value of `IP_TTL`.
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.mio.ttl()
+    }
+
+    /// Joins the IPv4 multicast group `multiaddr` on the interface with
+    /// address `interface`, or any interface if unspecified.
+    pub fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        self.mio.join_multicast_v4(&multiaddr, &interface)
+    }
+
+    /// Leaves the IPv4 multicast group `multiaddr`.
+    pub fn leave_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        self.mio.leave_multicast_v4(&multiaddr, &interface)
+    }
+
+    /// Joins the IPv6 multicast group `multiaddr` on the interface with
+    /// index `interface`, or any interface if `0`.
+    pub fn join_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.mio.join_multicast_v6(multiaddr, interface)
+    }
+
+    /// Leaves the IPv6 multicast group `multiaddr`.
+    pub fn leave_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.mio.leave_multicast_v6(multiaddr, interface)
+    }
+
+    /// Returns and clears the pending error on the socket (`SO_ERROR`).
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.mio.take_error()
+    }
+}
+
+impl AsRawFd for UdpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.mio.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.mio.fmt(f)
+    }
+}
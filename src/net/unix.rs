@@ -0,0 +1,40 @@
+//! Unix domain sockets.
+
+mod datagram;
+pub use datagram::UnixDatagram;
+
+mod listener;
+pub use listener::UnixListener;
+
+mod socket_addr;
+pub use socket_addr::SocketAddr;
+
+mod stream;
+pub use stream::UnixStream;
+
+mod ucred;
+pub use ucred::UCred;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use std::{io, path::PathBuf};
+
+/// Returns the path mio binds or connects to for the abstract socket
+/// address `name`, which mio recognizes by its leading nul byte.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn abstract_path(name: &[u8]) -> io::Result<PathBuf> {
+    if name.contains(&0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "abstract socket name may not contain nul bytes",
+        ));
+    }
+
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut path = Vec::with_capacity(name.len() + 1);
+    path.push(0);
+    path.extend_from_slice(name);
+
+    Ok(OsString::from_vec(path).into())
+}
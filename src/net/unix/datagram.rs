@@ -0,0 +1,196 @@
+use crate::net::sys;
+use crate::net::unix::SocketAddr;
+use crate::runtime::io::{Interest, Registration};
+use crate::runtime::Handle;
+
+use std::fmt;
+use std::io;
+use std::mem::ManuallyDrop;
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::task::{Context, Poll};
+use tokio::io::ReadBuf;
+
+/// A Unix datagram socket.
+pub struct UnixDatagram {
+    /// Socket registered with the I/O driver
+    registration: Registration,
+
+    /// Mio socket
+    mio: mio::net::UnixDatagram,
+}
+
+impl UnixDatagram {
+    /// Creates a socket bound to the filesystem path `path`.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<UnixDatagram> {
+        let mio = mio::net::UnixDatagram::bind(path)?;
+        UnixDatagram::new(mio)
+    }
+
+    /// Creates a socket bound to `name` in the abstract namespace.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn bind_abstract(name: impl AsRef<[u8]>) -> io::Result<UnixDatagram> {
+        let path = super::abstract_path(name.as_ref())?;
+        UnixDatagram::bind(path)
+    }
+
+    /// Creates a socket not bound to any address.
+    pub fn unbound() -> io::Result<UnixDatagram> {
+        let mio = mio::net::UnixDatagram::unbound()?;
+        UnixDatagram::new(mio)
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
+        let (a, b) = mio::net::UnixDatagram::pair()?;
+        Ok((UnixDatagram::new(a)?, UnixDatagram::new(b)?))
+    }
+
+    fn new(mut mio: mio::net::UnixDatagram) -> io::Result<UnixDatagram> {
+        Handle::with_current(|handle| {
+            let registration = handle.io().register(
+                handle,
+                &mut mio,
+                Interest::READABLE.add(Interest::WRITABLE),
+            )?;
+            Ok(UnixDatagram { registration, mio })
+        })
+    }
+
+    /// Connects the socket to the filesystem path `path`, so `send` and
+    /// `recv` may be used.
+    pub fn connect(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.mio.connect(path)
+    }
+
+    /// Connects the socket to `name` in the abstract namespace.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn connect_abstract(&self, name: impl AsRef<[u8]>) -> io::Result<()> {
+        let path = super::abstract_path(name.as_ref())?;
+        self.connect(path)
+    }
+
+    /// Returns the address the socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.local_addr().map(SocketAddr)
+    }
+
+    /// Returns the address the socket is connected to.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.peer_addr().map(SocketAddr)
+    }
+
+    /// Sends a datagram to the connected address, returning the number of
+    /// bytes sent.
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.registration
+            .async_io(Interest::WRITABLE, || self.mio.send(buf))
+            .await
+    }
+
+    /// Receives a datagram from the connected address, returning the number
+    /// of bytes read.
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.registration
+            .async_io(Interest::READABLE, || self.mio.recv(buf))
+            .await
+    }
+
+    /// Sends a datagram to the socket bound to `path`.
+    pub async fn send_to(&self, buf: &[u8], path: impl AsRef<Path>) -> io::Result<usize> {
+        let path = path.as_ref();
+
+        self.registration
+            .async_io(Interest::WRITABLE, || self.mio.send_to(buf, path))
+            .await
+    }
+
+    /// Sends a datagram to the socket bound to `name` in the abstract
+    /// namespace.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub async fn send_to_abstract(&self, buf: &[u8], name: impl AsRef<[u8]>) -> io::Result<usize> {
+        #[cfg(target_os = "android")]
+        use std::os::android::net::SocketAddrExt;
+        #[cfg(target_os = "linux")]
+        use std::os::linux::net::SocketAddrExt;
+        use std::os::unix::io::FromRawFd;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+
+        // mio only sends to paths, borrow the socket as a std one instead
+        let std = ManuallyDrop::new(unsafe {
+            std::os::unix::net::UnixDatagram::from_raw_fd(self.mio.as_raw_fd())
+        });
+
+        self.registration
+            .async_io(Interest::WRITABLE, || std.send_to_addr(buf, &addr))
+            .await
+    }
+
+    /// Receives a datagram, returning the number of bytes read and the
+    /// address it came from.
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        let (n, addr) = self
+            .registration
+            .async_io(Interest::READABLE, || self.mio.recv_from(buf))
+            .await?;
+
+        Ok((n, SocketAddr(addr)))
+    }
+
+    /// Attempts to receive a datagram from the connected address into
+    /// `buf`.
+    ///
+    /// Registers the current task to be woken once the socket is readable if
+    /// it is not.
+    pub fn poll_recv(&self, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let fd = self.mio.as_raw_fd();
+        // `recv` only writes initialized bytes
+        let unfilled = unsafe { buf.unfilled_mut() };
+
+        let n = match self
+            .registration
+            .poll_io(cx, Interest::READABLE, || sys::recv(fd, unfilled, 0))
+        {
+            Poll::Ready(Ok(n)) => n,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        unsafe { buf.assume_init(n) };
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+
+    /// Attempts to send a datagram to the connected address.
+    ///
+    /// Registers the current task to be woken once the socket is writable if
+    /// it is not.
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.registration
+            .poll_io(cx, Interest::WRITABLE, || self.mio.send(buf))
+    }
+
+    /// Shuts down the read, write, or both halves of the socket.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.mio.shutdown(how)
+    }
+
+    /// Returns and clears the pending error on the socket (`SO_ERROR`).
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.mio.take_error()
+    }
+}
+
+impl AsRawFd for UnixDatagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.mio.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for UnixDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.mio.fmt(f)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::net::unix::{SocketAddr, UnixStream};
+use crate::runtime::io::{Interest, Registration};
+use crate::runtime::Handle;
+
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::task::{Context, Poll};
+
+/// A Unix socket which can accept connections from other Unix sockets.
+pub struct UnixListener {
+    /// Socket registered with the I/O driver
+    registration: Registration,
+
+    /// Mio listener
+    mio: mio::net::UnixListener,
+}
+
+impl UnixListener {
+    /// Creates a new `UnixListener` bound to the filesystem path `path`.
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<UnixListener> {
+        let mio = mio::net::UnixListener::bind(path)?;
+        UnixListener::new(mio)
+    }
+
+    /// Creates a new `UnixListener` bound to `name` in the abstract
+    /// namespace, which leaves no file behind.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn bind_abstract(name: impl AsRef<[u8]>) -> io::Result<UnixListener> {
+        let path = super::abstract_path(name.as_ref())?;
+        UnixListener::bind(path)
+    }
+
+    fn new(mut mio: mio::net::UnixListener) -> io::Result<UnixListener> {
+        Handle::with_current(|handle| {
+            let registration = handle.io().register(handle, &mut mio, Interest::READABLE)?;
+            Ok(UnixListener { registration, mio })
+        })
+    }
+
+    /// Returns the address the listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.local_addr().map(SocketAddr)
+    }
+
+    /// Accepts a new incoming connection.
+    pub async fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        crate::future::poll_fn(|cx| self.poll_accept(cx)).await
+    }
+
+    /// Polls to accept a new incoming connection.
+    ///
+    /// If no connection is pending, registers the current task to be woken
+    /// once one is. Only the task of the last call is woken.
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(UnixStream, SocketAddr)>> {
+        let (mio, addr) = match self
+            .registration
+            .poll_io(cx, Interest::READABLE, || self.mio.accept())
+        {
+            Poll::Ready(Ok(ret)) => ret,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let stream = UnixStream::new(mio)?;
+        Poll::Ready(Ok((stream, SocketAddr(addr))))
+    }
+}
+
+impl AsRawFd for UnixListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.mio.as_raw_fd()
+    }
+}
+
+impl fmt::Debug for UnixListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.mio.fmt(f)
+    }
+}
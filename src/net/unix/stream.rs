@@ -0,0 +1,169 @@
+use crate::net::sys;
+use crate::net::unix::{ucred, SocketAddr, UCred};
+use crate::runtime::io::{Interest, Ready, Registration};
+use crate::runtime::Handle;
+
+use std::fmt;
+use std::io::{self, Read};
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A connected Unix stream socket.
+pub struct UnixStream {
+    /// Socket registered with the I/O driver
+    registration: Registration,
+
+    /// Mio stream
+    mio: mio::net::UnixStream,
+}
+
+impl UnixStream {
+    /// Connects to the socket bound to the filesystem path `path`.
+    pub async fn connect(path: impl AsRef<Path>) -> io::Result<UnixStream> {
+        let mio = mio::net::UnixStream::connect(path)?;
+        UnixStream::connect_mio(mio).await
+    }
+
+    /// Connects to the socket bound to `name` in the abstract namespace.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub async fn connect_abstract(name: impl AsRef<[u8]>) -> io::Result<UnixStream> {
+        let path = super::abstract_path(name.as_ref())?;
+        UnixStream::connect(path).await
+    }
+
+    async fn connect_mio(mio: mio::net::UnixStream) -> io::Result<UnixStream> {
+        let stream = UnixStream::new(mio)?;
+
+        // The socket becomes writable once the connection is established or
+        // has failed.
+        loop {
+            stream.registration.write_ready().await;
+
+            if let Some(e) = stream.mio.take_error()? {
+                return Err(e);
+            }
+
+            match stream.mio.peer_addr() {
+                Ok(_) => return Ok(stream),
+                // Spurious wake up, the connection is still in progress
+                Err(ref e) if e.kind() == io::ErrorKind::NotConnected => {
+                    stream.registration.clear_readiness(Ready::WRITABLE);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        let (a, b) = mio::net::UnixStream::pair()?;
+        Ok((UnixStream::new(a)?, UnixStream::new(b)?))
+    }
+
+    pub(crate) fn new(mut mio: mio::net::UnixStream) -> io::Result<UnixStream> {
+        Handle::with_current(|handle| {
+            let registration = handle.io().register(
+                handle,
+                &mut mio,
+                Interest::READABLE.add(Interest::WRITABLE),
+            )?;
+            Ok(UnixStream { registration, mio })
+        })
+    }
+
+    /// Returns the address of the local half of the connection.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.local_addr().map(SocketAddr)
+    }
+
+    /// Returns the address of the remote half of the connection.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.mio.peer_addr().map(SocketAddr)
+    }
+
+    /// Returns the credentials of the process on the other end of the
+    /// connection.
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        ucred::get_peer_cred(self.mio.as_raw_fd())
+    }
+
+    /// Returns and clears the pending error on the socket (`SO_ERROR`).
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.mio.take_error()
+    }
+
+    /// Reads some bytes into `buf`, returning how many were read.
+    ///
+    /// Returns `0` once the peer has shut down its write half.
+    pub async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        crate::future::poll_fn(|cx| self.poll_read_inner(cx, buf)).await
+    }
+
+    /// Writes some bytes from `buf`, waiting for the socket to become
+    /// writable if its send buffer is full.
+    pub async fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        crate::future::poll_fn(|cx| self.poll_write_inner(cx, buf)).await
+    }
+
+    /// Writes all of `buf`.
+    pub async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        crate::net::write_all(buf, |cx, buf| self.poll_write_inner(cx, buf)).await
+    }
+
+    fn poll_read_inner(&self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.registration
+            .poll_stream_io(cx, Interest::READABLE, buf.len(), || (&self.mio).read(buf))
+    }
+
+    fn poll_write_inner(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.registration
+            .poll_stream_io(cx, Interest::WRITABLE, buf.len(), || {
+                sys::send(self.mio.as_raw_fd(), buf)
+            })
+    }
+}
+
+impl AsRawFd for UnixStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.mio.as_raw_fd()
+    }
+}
+
+impl AsyncRead for UnixStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        crate::net::poll_read_buf(&self.registration, self.mio.as_raw_fd(), cx, buf)
+    }
+}
+
+impl AsyncWrite for UnixStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_inner(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // unix stream is always flushed
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.mio.shutdown(Shutdown::Write))
+    }
+}
+
+impl fmt::Debug for UnixStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.mio.fmt(f)
+    }
+}
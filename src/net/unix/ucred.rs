@@ -0,0 +1,63 @@
+use crate::net::sys;
+
+use std::io;
+use std::os::unix::io::RawFd;
+
+/// Credentials of the process on the other end of a Unix socket.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct UCred {
+    /// Process ID, if the platform reports it
+    pid: Option<libc::pid_t>,
+
+    /// User ID
+    uid: libc::uid_t,
+
+    /// Group ID
+    gid: libc::gid_t,
+}
+
+impl UCred {
+    /// Returns the user ID of the peer process.
+    pub fn uid(&self) -> libc::uid_t {
+        self.uid
+    }
+
+    /// Returns the group ID of the peer process.
+    pub fn gid(&self) -> libc::gid_t {
+        self.gid
+    }
+
+    /// Returns the process ID of the peer process.
+    ///
+    /// Only reported on Linux and Android.
+    pub fn pid(&self) -> Option<libc::pid_t> {
+        self.pid
+    }
+}
+
+/// Returns the credentials of the peer connected to `fd` (`SO_PEERCRED`).
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn get_peer_cred(fd: RawFd) -> io::Result<UCred> {
+    let cred: libc::ucred = sys::getsockopt(fd, libc::SOL_SOCKET, libc::SO_PEERCRED)?;
+
+    Ok(UCred {
+        pid: Some(cred.pid),
+        uid: cred.uid,
+        gid: cred.gid,
+    })
+}
+
+/// Returns the credentials of the peer connected to `fd`.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub(crate) fn get_peer_cred(fd: RawFd) -> io::Result<UCred> {
+    let mut uid = 0;
+    let mut gid = 0;
+
+    sys::cvt(unsafe { libc::getpeereid(fd, &mut uid, &mut gid) })?;
+
+    Ok(UCred {
+        pid: None,
+        uid,
+        gid,
+    })
+}
@@ -1,3 +1,6 @@
+mod builder;
+pub use builder::{Builder, UnhandledPanic};
+
 mod driver;
 use driver::Driver;
 
@@ -6,13 +9,19 @@ pub(crate) mod io;
 mod scheduler;
 use scheduler::Scheduler;
 
+pub(crate) mod time;
+
 pub(crate) mod task;
 use task::{JoinHandle, Task};
 
-use std::cell::RefCell;
+mod thread;
 
+use std::cell::RefCell;
+use std::ffi::CString;
 use std::future::Future;
-use std::rc::Rc;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
 
 pub struct Runtime {
     handle: Handle,
@@ -24,34 +33,52 @@ pub struct Handle {
     inner: Rc<Inner>,
 }
 
+/// Handle to the runtime that does not keep it alive
+pub(crate) struct WeakHandle {
+    inner: Weak<Inner>,
+}
+
 struct Inner {
     /// Executes tasks
-    scheduler: Scheduler,
+    scheduler: Rc<Scheduler>,
 
-    /// Receives events from the OS and dispatches them.
-    io: io::Handle,
+    /// Receives events from the OS and dispatches them, if enabled.
+    io: Option<io::Handle>,
+
+    /// Tracks timers, if enabled.
+    time: Option<time::Handle>,
 
     /// Holds scheduler & io state used to drive the runtime forward
     driver: RefCell<Driver>,
+
+    /// Name of the thread while it drives the runtime
+    thread_name: Option<CString>,
+
+    /// Called when a thread starts driving the runtime
+    on_thread_start: Option<Callback>,
+
+    /// Called when a thread stops driving the runtime
+    on_thread_stop: Option<Callback>,
+}
+
+type Callback = Rc<dyn Fn()>;
+
+/// Unsets the current runtime when dropped, even if the runtime panics.
+struct EnterGuard<'a> {
+    handle: &'a Handle,
+
+    /// Name of the thread before it started driving the runtime
+    prev_thread_name: Option<CString>,
+
+    _p: PhantomData<Rc<()>>,
 }
 
 thread_local!(static CURRENT: RefCell<Option<Handle>> = RefCell::new(None));
 
 impl Runtime {
-    /// Create a new runtime
+    /// Create a new runtime with the default configuration
     pub fn new() -> std::io::Result<Runtime> {
-        let (io_driver, io_handle) = io::driver()?;
-        let driver = Driver::new(io_driver);
-
-        Ok(Runtime {
-            handle: Handle {
-                inner: Rc::new(Inner {
-                    scheduler: Scheduler::new(),
-                    io: io_handle,
-                    driver: RefCell::new(driver),
-                }),
-            },
-        })
+        Builder::new().enable_all().build()
     }
 
     /// Spawn a task on the runtime
@@ -64,23 +91,69 @@ impl Runtime {
     }
 
     /// Block on a future
+    ///
+    /// Drives the future to completion on the current thread, executing
+    /// spawned tasks and processing I/O events while it is pending, and
+    /// returns its output as soon as it is ready.
     pub fn block_on<T: Future>(&self, task: T) -> T::Output {
-        todo!();
+        // Entering first makes a nested call fail the nesting assertion
+        // rather than find the driver already borrowed
+        let _enter = self.handle.enter();
+        let mut driver = self.handle.inner.driver.borrow_mut();
+
+        self.handle
+            .inner
+            .scheduler
+            .block_on(&self.handle, &mut driver, task)
     }
 
     /// Execute the runtime until all tasks have completed
     pub fn run(&self) {
+        let _enter = self.handle.enter();
         let mut driver = self.handle.inner.driver.borrow_mut();
 
-        CURRENT.with(|c| {
-            *c.borrow_mut() = Some(self.handle.clone());
-        });
+        self.handle
+            .inner
+            .scheduler
+            .run(&self.handle, &mut driver, None);
+    }
 
-        self.handle.inner.scheduler.run(&self.handle, &mut driver);
+    /// Execute the runtime until all tasks have completed or `duration` has
+    /// elapsed, then shut it down.
+    ///
+    /// Tasks that have not completed by then are cancelled, as when the
+    /// runtime is dropped.
+    pub fn shutdown_timeout(self, duration: Duration) {
+        let deadline = std::time::Instant::now().checked_add(duration);
 
-        CURRENT.with(|c| {
-            *c.borrow_mut() = None;
-        });
+        let _enter = self.handle.enter();
+        let mut driver = self.handle.inner.driver.borrow_mut();
+
+        self.handle
+            .inner
+            .scheduler
+            .run(&self.handle, &mut driver, deadline);
+    }
+}
+
+impl Drop for Runtime {
+    /// Cancels all remaining tasks, dropping their futures along with any
+    /// resources they own, then deregisters all I/O resources still open.
+    fn drop(&mut self) {
+        // Futures may reach for the current runtime while being dropped,
+        // possibly from within another runtime.
+        let prev = CURRENT.try_with(|c| c.replace(Some(self.handle.clone())));
+
+        let scheduler = &self.handle.inner.scheduler;
+        scheduler.shutdown();
+
+        if let Some(ref io) = self.handle.inner.io {
+            io.shutdown(scheduler);
+        }
+
+        if let Ok(prev) = prev {
+            CURRENT.with(|c| *c.borrow_mut() = prev);
+        }
     }
 }
 
@@ -98,9 +171,56 @@ impl Handle {
         &self.inner.scheduler
     }
 
+    pub(crate) fn downgrade(&self) -> WeakHandle {
+        WeakHandle {
+            inner: Rc::downgrade(&self.inner),
+        }
+    }
+
     /// Returns a reference to the I/O handle
+    #[track_caller]
     pub(crate) fn io(&self) -> &io::Handle {
-        &self.inner.io
+        self.inner.io.as_ref().expect(
+            "A runtime with the I/O driver disabled cannot perform I/O. Call `enable_io` on the runtime builder to enable it.",
+        )
+    }
+
+    /// Returns a reference to the time handle
+    #[track_caller]
+    pub(crate) fn time(&self) -> &time::Handle {
+        self.try_time().expect(
+            "A runtime with the time driver disabled cannot use timers. Call `enable_time` on the runtime builder to enable it.",
+        )
+    }
+
+    /// Returns a reference to the time handle, if enabled
+    pub(crate) fn try_time(&self) -> Option<&time::Handle> {
+        self.inner.time.as_ref()
+    }
+
+    /// Set this handle as the current runtime until the guard is dropped.
+    #[track_caller]
+    fn enter(&self) -> EnterGuard<'_> {
+        CURRENT.with(|c| {
+            let mut current = c.borrow_mut();
+            assert!(
+                current.is_none(),
+                "cannot start a runtime from within a runtime"
+            );
+            *current = Some(self.clone());
+        });
+
+        let guard = EnterGuard {
+            handle: self,
+            prev_thread_name: self.inner.thread_name.as_deref().and_then(thread::set_name),
+            _p: PhantomData,
+        };
+
+        if let Some(ref f) = self.inner.on_thread_start {
+            f();
+        }
+
+        guard
     }
 
     #[track_caller]
@@ -111,4 +231,35 @@ impl Handle {
             f(current)
         })
     }
+
+    /// Like `with_current`, returning `None` outside of a runtime.
+    pub(crate) fn try_with_current<R>(f: impl FnOnce(&Handle) -> R) -> Option<R> {
+        CURRENT
+            .try_with(|current| current.borrow().as_ref().map(f))
+            .ok()
+            .flatten()
+    }
+}
+
+impl WeakHandle {
+    /// Returns `None` once the runtime has been freed.
+    pub(crate) fn upgrade(&self) -> Option<Handle> {
+        self.inner.upgrade().map(|inner| Handle { inner })
+    }
+}
+
+impl Drop for EnterGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(ref f) = self.handle.inner.on_thread_stop {
+            f();
+        }
+
+        if let Some(ref name) = self.prev_thread_name {
+            thread::set_name(name);
+        }
+
+        CURRENT.with(|c| {
+            *c.borrow_mut() = None;
+        });
+    }
 }
@@ -0,0 +1,278 @@
+use crate::runtime::driver::Unpark;
+use crate::runtime::{io, time, Callback, Driver, Handle, Inner, Runtime, Scheduler};
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::fmt;
+use std::rc::Rc;
+use std::thread;
+
+/// Builds a runtime with custom configuration values.
+///
+/// # Examples
+///
+/// ```
+/// use stokio::runtime::{Builder, UnhandledPanic};
+///
+/// let rt = Builder::new()
+///     .enable_all()
+///     .event_interval(31)
+///     .unhandled_panic(UnhandledPanic::ShutdownRuntime)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct Builder {
+    /// Whether or not to enable the I/O driver
+    enable_io: bool,
+
+    /// Whether or not to enable the time driver
+    enable_time: bool,
+
+    /// Initial capacity of the run queue
+    queue_capacity: usize,
+
+    /// Maximum number of I/O events received per call to the OS
+    event_capacity: usize,
+
+    /// Initial capacity of the I/O resources slab
+    resource_capacity: usize,
+
+    /// Number of tasks polled before checking for I/O events and timers
+    event_interval: u32,
+
+    /// Name given to the thread driving the runtime
+    thread_name: Option<String>,
+
+    /// Called when a thread starts driving the runtime
+    on_thread_start: Option<Callback>,
+
+    /// Called when a thread stops driving the runtime
+    on_thread_stop: Option<Callback>,
+
+    /// How to react to a spawned task panicking
+    unhandled_panic: UnhandledPanic,
+}
+
+/// How the runtime should respond to unhandled panics in spawned tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UnhandledPanic {
+    /// The panic is caught and returned as a `JoinError::Panic` when
+    /// awaiting the task's `JoinHandle`. The runtime keeps running other
+    /// tasks.
+    ///
+    /// This is the default behavior.
+    Ignore,
+
+    /// The runtime shuts down as soon as a spawned task panics: no other
+    /// task is polled and `block_on` and `run` panic.
+    ///
+    /// This applies whether or not the task's `JoinHandle` was dropped.
+    ShutdownRuntime,
+}
+
+const DEFAULT_QUEUE_CAPACITY: usize = 256;
+const DEFAULT_EVENT_CAPACITY: usize = 1024;
+const DEFAULT_RESOURCE_CAPACITY: usize = 256;
+const DEFAULT_EVENT_INTERVAL: u32 = 61;
+
+impl Builder {
+    /// Returns a new builder with the default configuration values.
+    ///
+    /// Neither the I/O nor the time driver are enabled.
+    pub fn new() -> Builder {
+        Builder {
+            enable_io: false,
+            enable_time: false,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            event_capacity: DEFAULT_EVENT_CAPACITY,
+            resource_capacity: DEFAULT_RESOURCE_CAPACITY,
+            event_interval: DEFAULT_EVENT_INTERVAL,
+            thread_name: None,
+            on_thread_start: None,
+            on_thread_stop: None,
+            unhandled_panic: UnhandledPanic::Ignore,
+        }
+    }
+
+    /// Enables both the I/O and time drivers.
+    pub fn enable_all(&mut self) -> &mut Self {
+        self.enable_io().enable_time()
+    }
+
+    /// Enables the I/O driver, required by the `net` types.
+    pub fn enable_io(&mut self) -> &mut Self {
+        self.enable_io = true;
+        self
+    }
+
+    /// Enables the time driver, required by the `time` utilities.
+    pub fn enable_time(&mut self) -> &mut Self {
+        self.enable_time = true;
+        self
+    }
+
+    /// Sets the initial capacity of the queue of tasks scheduled to run.
+    ///
+    /// The queue grows as needed. The default is 256.
+    pub fn queue_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.queue_capacity = capacity;
+        self
+    }
+
+    /// Sets the maximum number of I/O events received from the OS at once.
+    ///
+    /// Remaining events are received on the next check. The default is 1024.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[track_caller]
+    pub fn event_capacity(&mut self, capacity: usize) -> &mut Self {
+        assert!(capacity > 0, "event capacity must be greater than 0");
+        self.event_capacity = capacity;
+        self
+    }
+
+    /// Sets the initial capacity of the table of registered I/O resources,
+    /// such as sockets.
+    ///
+    /// The table grows as needed. The default is 256.
+    pub fn resource_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.resource_capacity = capacity;
+        self
+    }
+
+    /// Sets the number of tasks polled before checking for I/O events and
+    /// elapsed timers, even if more tasks are ready to run.
+    ///
+    /// A lower value reduces the latency of I/O, a higher value reduces the
+    /// overhead of checking for it. The default is 61.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    #[track_caller]
+    pub fn event_interval(&mut self, interval: u32) -> &mut Self {
+        assert!(interval > 0, "event_interval must be greater than 0");
+        self.event_interval = interval;
+        self
+    }
+
+    /// Sets the name of the thread while it drives the runtime.
+    ///
+    /// The runtime executes on the thread calling `block_on` or `run`, which
+    /// is renamed for the duration of the call. Only supported on Linux,
+    /// where names are truncated to 15 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a nul byte.
+    #[track_caller]
+    pub fn thread_name(&mut self, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        assert!(
+            !name.contains('\0'),
+            "thread name may not contain nul bytes"
+        );
+        self.thread_name = Some(name);
+        self
+    }
+
+    /// Executes function `f` when a thread starts driving the runtime, on
+    /// entering `block_on` or `run`.
+    ///
+    /// The runtime is current while `f` runs, so it may spawn tasks.
+    pub fn on_thread_start<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() + 'static,
+    {
+        self.on_thread_start = Some(Rc::new(f));
+        self
+    }
+
+    /// Executes function `f` when a thread stops driving the runtime, on
+    /// returning from `block_on` or `run`.
+    pub fn on_thread_stop<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn() + 'static,
+    {
+        self.on_thread_stop = Some(Rc::new(f));
+        self
+    }
+
+    /// Configure how the runtime responds to a spawned task panicking.
+    pub fn unhandled_panic(&mut self, behavior: UnhandledPanic) -> &mut Self {
+        self.unhandled_panic = behavior;
+        self
+    }
+
+    /// Creates the configured `Runtime`.
+    ///
+    /// The runtime is bound to the calling thread: tasks woken on other
+    /// threads are handed over to it and, with the I/O driver disabled, those
+    /// wakes unpark it. It must be driven, with `block_on` or `run`, on the
+    /// thread that built it, which `Runtime` not being `Send` guarantees.
+    pub fn build(&mut self) -> std::io::Result<Runtime> {
+        let (io_driver, io_handle) = if self.enable_io {
+            let (driver, handle) = io::driver(self.event_capacity, self.resource_capacity)?;
+            (Some(driver), Some(handle))
+        } else {
+            (None, None)
+        };
+
+        let time_handle = self.enable_time.then(time::Handle::new);
+
+        let unpark = match io_handle {
+            Some(ref io) => Unpark::Io(io.waker()),
+            None => Unpark::Thread(thread::current()),
+        };
+
+        let scheduler = Scheduler::new(
+            self.queue_capacity,
+            self.event_interval,
+            self.unhandled_panic,
+            unpark,
+        );
+
+        let thread_name = self
+            .thread_name
+            .as_ref()
+            .map(|name| CString::new(name.as_str()).unwrap());
+
+        Ok(Runtime {
+            handle: Handle {
+                inner: Rc::new(Inner {
+                    scheduler: Rc::new(scheduler),
+                    io: io_handle,
+                    time: time_handle,
+                    driver: RefCell::new(Driver::new(io_driver)),
+                    thread_name,
+                    on_thread_start: self.on_thread_start.clone(),
+                    on_thread_stop: self.on_thread_stop.clone(),
+                }),
+            },
+        })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Builder {
+        Builder::new()
+    }
+}
+
+impl fmt::Debug for Builder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Builder")
+            .field("enable_io", &self.enable_io)
+            .field("enable_time", &self.enable_time)
+            .field("queue_capacity", &self.queue_capacity)
+            .field("event_capacity", &self.event_capacity)
+            .field("resource_capacity", &self.resource_capacity)
+            .field("event_interval", &self.event_interval)
+            .field("thread_name", &self.thread_name)
+            .field("unhandled_panic", &self.unhandled_panic)
+            .finish()
+    }
+}
@@ -1,15 +1,118 @@
 use crate::runtime::{io, Handle, Scheduler};
 
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
 pub(crate) struct Driver {
-    io: io::Driver,
+    /// `None` if the I/O driver is disabled
+    io: Option<io::Driver>,
+}
+
+/// Interrupts the driver blocked in `park`, from any thread.
+pub(crate) enum Unpark {
+    /// Wakes the I/O driver's `mio::Poll`
+    Io(Arc<mio::Waker>),
+
+    /// Unparks the thread driving the runtime, when the I/O driver is
+    /// disabled
+    Thread(thread::Thread),
 }
 
 impl Driver {
-    pub(crate) fn new(io: io::Driver) -> Driver {
+    pub(crate) fn new(io: Option<io::Driver>) -> Driver {
         Driver { io }
     }
 
-    pub(crate) fn park(&mut self, handle: &Handle, scheduler: &Scheduler) -> io::Result<()> {
-        self.io.park(handle.io(), scheduler)
+    /// Block until an I/O event is received, the next timer fires or
+    /// `timeout` elapses.
+    ///
+    /// If the clock is paused, checks for I/O events without blocking and,
+    /// if there are none, advances the clock to the next timer instead.
+    pub(crate) fn park(
+        &mut self,
+        handle: &Handle,
+        scheduler: &Scheduler,
+        timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        let time = match handle.try_time() {
+            Some(time) => time,
+            None => {
+                self.park_io(handle, scheduler, timeout)?;
+                return Ok(());
+            }
+        };
+        let clock = time.clock();
+
+        match time.next_deadline() {
+            Some(deadline) if clock.is_paused() => {
+                let woken = self.park_io(handle, scheduler, Some(Duration::ZERO))?;
+
+                if !woken {
+                    clock.advance(deadline.saturating_duration_since(clock.now()));
+                }
+            }
+            Some(deadline) => {
+                let until_deadline = deadline.saturating_duration_since(clock.now());
+                let timeout = timeout.map_or(until_deadline, |t| t.min(until_deadline));
+                self.park_io(handle, scheduler, Some(timeout))?;
+            }
+            None => {
+                self.park_io(handle, scheduler, timeout)?;
+            }
+        }
+
+        time.process(clock.now());
+
+        Ok(())
+    }
+
+    /// Dispatch pending I/O events and elapsed timers without blocking.
+    pub(crate) fn poll(&mut self, handle: &Handle, scheduler: &Scheduler) -> io::Result<()> {
+        self.park_io(handle, scheduler, Some(Duration::ZERO))?;
+
+        if let Some(time) = handle.try_time() {
+            time.process(time.clock().now());
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if any I/O event was received.
+    ///
+    /// Without an I/O driver there is nothing to wait on but the timeout.
+    fn park_io(
+        &mut self,
+        handle: &Handle,
+        scheduler: &Scheduler,
+        timeout: Option<Duration>,
+    ) -> io::Result<bool> {
+        match self.io {
+            Some(ref mut io) => io.park(handle.io(), scheduler, timeout),
+            None => {
+                // Parking, rather than sleeping, lets `Unpark` interrupt the
+                // wait
+                match timeout {
+                    Some(timeout) if timeout.is_zero() => {}
+                    Some(timeout) => thread::park_timeout(timeout),
+                    None => thread::park(),
+                }
+
+                Ok(false)
+            }
+        }
+    }
+}
+
+impl Unpark {
+    pub(crate) fn unpark(&self) {
+        match self {
+            // Fails only if the OS is out of resources, in which case the
+            // driver wakes up on the next event or timer anyway.
+            Unpark::Io(waker) => {
+                let _ = waker.wake();
+            }
+            Unpark::Thread(thread) => thread.unpark(),
+        }
     }
 }
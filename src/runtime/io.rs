@@ -7,12 +7,16 @@ pub use ready::Ready;
 use crate::runtime::{self, Scheduler, Task};
 
 use mio::event::Source;
+use mio::unix::SourceFd;
 use mio::Token;
 use slab::Slab;
 use std::cell::{Cell, RefCell};
 use std::io;
-use std::task::{self, Poll};
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{self, Poll, Waker};
+use std::time::Duration;
 
 pub(crate) use std::io::Result;
 
@@ -22,6 +26,15 @@ pub(crate) struct Handle {
 
     /// Tracks state for open sockets and other resources
     resources: RefCell<Slab<Rc<Resource>>>,
+
+    /// Generation of the next registered resource
+    next_generation: Cell<usize>,
+
+    /// Set once the runtime is shutting down
+    is_shutdown: Cell<bool>,
+
+    /// Interrupts `Driver::park` from other threads
+    waker: Arc<mio::Waker>,
 }
 
 /// Used by the runtime to process I/O events
@@ -36,40 +49,71 @@ pub(crate) struct Driver {
 pub(crate) struct Resource {
     /// Handle to the runtime
     ///
-    /// TODO: break cycle
-    rt: runtime::Handle,
+    /// Weak, as the runtime owns the resource through its slab.
+    rt: runtime::WeakHandle,
+
+    /// Registered file descriptor
+    fd: RawFd,
 
-    /// Which slot in the slab this resource is stored in.
-    // key: usize,
+    /// Token the resource is registered with, encoding its key in the slab
+    /// and its generation.
+    token: Token,
 
     /// Current resource readiness
     readiness: Cell<Ready>,
 
-    /// Task to schedule on readable
-    read_task: RefCell<Option<Task>>,
+    /// Notified on readable
+    read_waiter: RefCell<Option<Waiter>>,
 
-    /// Task to schedule on writable
-    write_task: RefCell<Option<Task>>,
+    /// Notified on writable
+    write_waiter: RefCell<Option<Waiter>>,
 }
 
+/// Waiting on a resource to become ready
+enum Waiter {
+    /// Task of this runtime, scheduled directly.
+    Task(Task),
+
+    /// Any other waker, e.g. one wrapping the task's waker in a combinator.
+    Waker(Waker),
+}
+
+/// Deregisters the resource from the I/O driver when dropped.
+///
+/// Must be dropped before the registered source is closed, so it is declared
+/// before the source in the owning struct.
 pub(crate) struct Registration {
     resource: Rc<Resource>,
 }
 
-const INITIAL_RESOURCES_CAPACITY: usize = 256;
-const INITIAL_EVENTS_CAPACITY: usize = 1024;
+/// Tokens pack the resource's key in the slab with a generation, so events
+/// for a released key are not dispatched to the resource reusing it.
+const KEY_BITS: u32 = 24;
+const KEY_MASK: usize = (1 << KEY_BITS) - 1;
+const MAX_GENERATION: usize = usize::MAX >> KEY_BITS;
+
+/// Token of the driver's `mio::Waker`, never given to a resource as their
+/// keys are below `KEY_MASK`.
+const WAKE_TOKEN: Token = Token(KEY_MASK);
 
-pub(crate) fn driver() -> io::Result<(Driver, Handle)> {
+pub(crate) fn driver(
+    event_capacity: usize,
+    resource_capacity: usize,
+) -> io::Result<(Driver, Handle)> {
     let mio = mio::Poll::new()?;
+    let waker = mio::Waker::new(mio.registry(), WAKE_TOKEN)?;
 
     let handle = Handle {
         mio: mio.registry().try_clone()?,
-        resources: RefCell::new(Slab::with_capacity(INITIAL_RESOURCES_CAPACITY)),
+        resources: RefCell::new(Slab::with_capacity(resource_capacity)),
+        next_generation: Cell::new(0),
+        is_shutdown: Cell::new(false),
+        waker: Arc::new(waker),
     };
 
     let driver = Driver {
         mio,
-        events: mio::Events::with_capacity(INITIAL_EVENTS_CAPACITY),
+        events: mio::Events::with_capacity(event_capacity),
     };
 
     Ok((driver, handle))
@@ -79,111 +123,172 @@ impl Handle {
     pub(crate) fn register(
         &self,
         rt: &runtime::Handle,
-        io: &mut impl Source,
+        io: &mut (impl Source + AsRawFd),
         interest: Interest,
     ) -> io::Result<Registration> {
-        // Reserve a new slot for the new resource. We will be using the key as
-        // the mio token.
-        // let mut resources = self.resources.borrow_mut();
-        // let entry = resources.vacant_entry();
+        if self.is_shutdown.get() {
+            return Err(io::Error::other("the runtime is shutting down"));
+        }
+
+        // Reserve a new slot for the new resource. The key and a generation
+        // make up the mio token.
+        let mut resources = self.resources.borrow_mut();
+        let entry = resources.vacant_entry();
+
+        if entry.key() >= KEY_MASK {
+            return Err(io::Error::other(
+                "reached the maximum number of registered resources",
+            ));
+        }
+
+        let generation = self.next_generation.get();
+        self.next_generation.set((generation + 1) & MAX_GENERATION);
+
+        let token = Token((generation << KEY_BITS) | entry.key());
 
         let resource = Rc::new(Resource {
-            rt: rt.clone(),
-            // key: entry.key(),
+            rt: rt.downgrade(),
+            fd: io.as_raw_fd(),
+            token,
             readiness: Cell::new(Ready::EMPTY),
-            read_task: RefCell::new(None),
-            write_task: RefCell::new(None),
+            read_waiter: RefCell::new(None),
+            write_waiter: RefCell::new(None),
         });
 
-        // Leak
-        let ptr = Rc::into_raw(resource.clone());
-
         // Register the socket with mio
-        self.mio.register(io, Token(ptr as _), interest.to_mio())?;
+        self.mio.register(io, token, interest.to_mio())?;
+
+        entry.insert(resource.clone());
 
         Ok(Registration { resource })
     }
+
+    /// Returns the waker interrupting `Driver::park`.
+    pub(crate) fn waker(&self) -> Arc<mio::Waker> {
+        self.waker.clone()
+    }
+
+    /// Returns the resource registered with `token`, if it is still
+    /// registered.
+    fn resource(&self, token: Token) -> Option<Rc<Resource>> {
+        let resources = self.resources.borrow();
+        let resource = resources.get(token.0 & KEY_MASK)?;
+
+        if resource.token == token {
+            Some(resource.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Add `ready` to the readiness of the resource registered with `token`.
+    ///
+    /// The resource may have been dropped since the event was received, its
+    /// slot possibly reused by another resource.
+    fn dispatch(&self, scheduler: &Scheduler, token: Token, ready: Ready) {
+        if let Some(resource) = self.resource(token) {
+            resource.add_readiness(scheduler, ready);
+        }
+    }
+
+    /// Remove the resource from mio and free its slot, if it is still
+    /// registered.
+    fn deregister(&self, resource: &Resource) {
+        let key = resource.token.0 & KEY_MASK;
+        let mut resources = self.resources.borrow_mut();
+
+        match resources.get(key) {
+            Some(registered) if registered.token == resource.token => {}
+            _ => return,
+        }
+
+        resources.remove(key);
+        drop(resources);
+
+        // Errors are ignored: the registration is being dropped and nothing
+        // is left to report them to.
+        let _ = self.mio.deregister(&mut SourceFd(&resource.fd));
+    }
+
+    /// Deregister all resources, waking their waiters.
+    ///
+    /// Resources dropped afterwards are not registered anymore, and new
+    /// resources cannot be registered.
+    pub(crate) fn shutdown(&self, scheduler: &Scheduler) {
+        self.is_shutdown.set(true);
+
+        let resources: Vec<_> = self.resources.borrow_mut().drain().collect();
+
+        for resource in resources {
+            let _ = self.mio.deregister(&mut SourceFd(&resource.fd));
+
+            // Pending operations observe the resource as closed
+            resource.add_readiness(scheduler, Ready::ALL);
+        }
+    }
 }
 
 impl Driver {
-    pub(crate) fn park(&mut self, handle: &Handle, scheduler: &Scheduler) -> io::Result<()> {
-        match self.mio.poll(&mut &mut self.events, None) {
+    /// Wait for I/O events for up to `timeout` and dispatch them.
+    ///
+    /// Returns true if any event was received.
+    pub(crate) fn park(
+        &mut self,
+        handle: &Handle,
+        scheduler: &Scheduler,
+        timeout: Option<Duration>,
+    ) -> io::Result<bool> {
+        match self.mio.poll(&mut self.events, timeout) {
             Ok(_) => {}
             Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
             Err(e) => return Err(e),
         }
 
         for event in self.events.iter() {
-            {
-                /*
-                let resources = handle.resources.borrow();
-                let resource = match resources.get(event.token().0) {
-                    Some(resource) => resource,
-                    None => continue,
-                };
-                */
-
-                let resource = event.token().0 as *const Resource;
-
-                unsafe {
-                    (*resource).add_readiness(scheduler, Ready::from_mio(event));
-                }
+            // Only interrupts the poll, the woken tasks are queued by the
+            // scheduler.
+            if event.token() == WAKE_TOKEN {
+                continue;
             }
+
+            handle.dispatch(scheduler, event.token(), Ready::from_mio(event));
         }
 
-        Ok(())
+        Ok(!self.events.is_empty())
     }
 }
 
 impl Registration {
     pub(crate) async fn read_ready(&self) -> Ready {
-        crate::future::poll_fn(|cx| {
-            self.poll_read_ready(cx)
-        })
-        .await
+        crate::future::poll_fn(|cx| self.poll_read_ready(cx)).await
     }
 
     pub(crate) fn poll_read_ready(&self, cx: &mut task::Context<'_>) -> Poll<Ready> {
         let ready = self.resource.readiness.get();
 
-        if ready.is_readable() {
+        if ready.satisfies(Interest::READABLE) {
             Poll::Ready(ready)
         } else {
-            // Get the runtime task associated with the current waker
-            let task = self
-                .resource
-                .rt
-                .scheduler()
-                .waker_to_task(cx.waker())
-                .expect("TODO");
-
-            *self.resource.read_task.borrow_mut() = Some(task);
-
+            self.resource
+                .set_waiter(&self.resource.read_waiter, cx.waker());
             Poll::Pending
         }
     }
 
     pub(crate) async fn write_ready(&self) -> Ready {
-        crate::future::poll_fn(|cx| {
-            let ready = self.resource.readiness.get();
-
-            if ready.is_writable() {
-                Poll::Ready(ready)
-            } else {
-                // Get the runtime task associated with the current waker
-                let task = self
-                    .resource
-                    .rt
-                    .scheduler()
-                    .waker_to_task(cx.waker())
-                    .expect("TODO");
-
-                *self.resource.write_task.borrow_mut() = Some(task);
-
-                Poll::Pending
-            }
-        })
-        .await
+        crate::future::poll_fn(|cx| self.poll_write_ready(cx)).await
+    }
+
+    pub(crate) fn poll_write_ready(&self, cx: &mut task::Context<'_>) -> Poll<Ready> {
+        let ready = self.resource.readiness.get();
+
+        if ready.satisfies(Interest::WRITABLE) {
+            Poll::Ready(ready)
+        } else {
+            self.resource
+                .set_waiter(&self.resource.write_waiter, cx.waker());
+            Poll::Pending
+        }
     }
 
     pub(crate) fn clear_readiness(&self, ready: Ready) {
@@ -192,36 +297,113 @@ impl Registration {
             .set(self.resource.readiness.get() - ready);
     }
 
-    pub(crate) async fn async_read<R>(
+    fn poll_ready(&self, cx: &mut task::Context<'_>, interest: Interest) -> Poll<Ready> {
+        if interest.is_readable() {
+            self.poll_read_ready(cx)
+        } else {
+            self.poll_write_ready(cx)
+        }
+    }
+
+    /// Call `f` once the resource is ready for `interest`, clearing the
+    /// readiness and waiting again if it would block. Interrupted calls are
+    /// retried.
+    pub(crate) fn poll_io<R>(
         &self,
+        cx: &mut task::Context<'_>,
+        interest: Interest,
         mut f: impl FnMut() -> io::Result<R>,
-    ) -> io::Result<R> {
+    ) -> Poll<io::Result<R>> {
         loop {
-            let ready = self.read_ready().await;
+            if self.poll_ready(cx, interest).is_pending() {
+                return Poll::Pending;
+            }
 
-            match f() {
-                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                    self.clear_readiness(Ready::READABLE);
-                }
-                x => return x,
+            match self.try_io(interest, &mut f) {
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                x => return Poll::Ready(x),
             }
         }
     }
 
-    pub(crate) async fn async_write<R>(
+    /// Call `f` without waiting, clearing the readiness for `interest` if it
+    /// would block.
+    pub(crate) fn try_io<R>(
         &self,
+        interest: Interest,
+        f: impl FnOnce() -> io::Result<R>,
+    ) -> io::Result<R> {
+        let ret = f();
+
+        if let Err(ref e) = ret {
+            if e.kind() == io::ErrorKind::WouldBlock {
+                self.clear_readiness(consumed(interest));
+            }
+        }
+
+        ret
+    }
+
+    pub(crate) async fn async_io<R>(
+        &self,
+        interest: Interest,
         mut f: impl FnMut() -> io::Result<R>,
     ) -> io::Result<R> {
-        loop {
-            let ready = self.write_ready().await;
+        crate::future::poll_fn(|cx| self.poll_io(cx, interest, &mut f)).await
+    }
+
+    /// `poll_io` for a stream read or write of up to `len` bytes.
+    pub(crate) fn poll_stream_io(
+        &self,
+        cx: &mut task::Context<'_>,
+        interest: Interest,
+        len: usize,
+        mut f: impl FnMut() -> io::Result<usize>,
+    ) -> Poll<io::Result<usize>> {
+        self.poll_io(cx, interest, || self.clear_if_short(interest, len, f()))
+    }
+
+    /// `try_io` for a stream read or write of up to `len` bytes.
+    pub(crate) fn try_stream_io(
+        &self,
+        interest: Interest,
+        len: usize,
+        f: impl FnOnce() -> io::Result<usize>,
+    ) -> io::Result<usize> {
+        self.try_io(interest, || self.clear_if_short(interest, len, f()))
+    }
 
-            match f() {
-                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                    self.clear_readiness(Ready::WRITABLE);
-                }
-                x => return x,
+    fn clear_if_short(
+        &self,
+        interest: Interest,
+        len: usize,
+        ret: io::Result<usize>,
+    ) -> io::Result<usize> {
+        // A partial transfer indicates the socket buffer has been drained or
+        // filled, the next call would block
+        if let Ok(n) = ret {
+            if n > 0 && n < len {
+                self.clear_readiness(consumed(interest));
             }
         }
+
+        ret
+    }
+}
+
+/// Readiness used up by an operation for `interest` that would block. The
+/// closed states are final and never cleared.
+fn consumed(interest: Interest) -> Ready {
+    Ready::from_interest(interest) - Ready::READ_CLOSED - Ready::WRITE_CLOSED
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Once the runtime is gone, the resource was deregistered on shutdown
+        if let Some(rt) = self.resource.rt.upgrade() {
+            rt.io().deregister(&self.resource);
+        }
     }
 }
 
@@ -233,18 +415,106 @@ impl Resource {
 
         self.readiness.set(old | ready);
 
-        if add.is_readable() {
-            let maybe_task = self.read_task.borrow_mut().take();
-            if let Some(task) = maybe_task {
-                scheduler.schedule(task);
+        if add.satisfies(Interest::READABLE) {
+            let maybe_waiter = self.read_waiter.borrow_mut().take();
+            if let Some(waiter) = maybe_waiter {
+                waiter.wake(scheduler);
+            }
+        }
+
+        if add.satisfies(Interest::WRITABLE) {
+            let maybe_waiter = self.write_waiter.borrow_mut().take();
+            if let Some(waiter) = maybe_waiter {
+                waiter.wake(scheduler);
             }
         }
+    }
 
-        if add.is_writable() {
-            let maybe_task = self.write_task.borrow_mut().take();
-            if let Some(task) = maybe_task {
-                scheduler.schedule(task);
+    /// Store the waiter to notify for `waker`, reusing the existing one if
+    /// it would wake the same task.
+    fn set_waiter(&self, slot: &RefCell<Option<Waiter>>, waker: &Waker) {
+        let mut slot = slot.borrow_mut();
+
+        if let Some(Waiter::Waker(existing)) = &*slot {
+            if existing.will_wake(waker) {
+                return;
             }
         }
+
+        // Fast path: the waker belongs to the task currently being polled
+        let task = self
+            .rt
+            .upgrade()
+            .and_then(|rt| rt.scheduler().waker_to_task(waker));
+
+        let waiter = match task {
+            Some(task) => Waiter::Task(task),
+            None => Waiter::Waker(waker.clone()),
+        };
+
+        *slot = Some(waiter);
+    }
+}
+
+impl Waiter {
+    fn wake(self, scheduler: &Scheduler) {
+        match self {
+            Waiter::Task(task) => scheduler.schedule(task),
+            Waiter::Waker(waker) => waker.wake(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::Runtime;
+
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::task::Wake;
+
+    struct Flag(AtomicBool);
+
+    impl Wake for Flag {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn socket() -> mio::net::UdpSocket {
+        mio::net::UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn event_for_released_slot_is_not_dispatched() {
+        let rt = Runtime::new().unwrap();
+        let handle = &rt.handle;
+        let io = handle.io();
+
+        let mut old = socket();
+        let registration = io.register(handle, &mut old, Interest::READABLE).unwrap();
+        let old_token = registration.resource.token;
+        drop(registration);
+
+        let mut new = socket();
+        let registration = io.register(handle, &mut new, Interest::READABLE).unwrap();
+        let new_token = registration.resource.token;
+
+        // The slot is reused, with another generation
+        assert_eq!(old_token.0 & KEY_MASK, new_token.0 & KEY_MASK);
+        assert_ne!(old_token, new_token);
+
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = task::Context::from_waker(&waker);
+        assert!(registration.poll_read_ready(&mut cx).is_pending());
+
+        io.dispatch(handle.scheduler(), old_token, Ready::READABLE);
+        assert!(!flag.0.load(Ordering::SeqCst));
+        assert!(registration.poll_read_ready(&mut cx).is_pending());
+
+        io.dispatch(handle.scheduler(), new_token, Ready::READABLE);
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert!(registration.poll_read_ready(&mut cx).is_ready());
     }
 }
@@ -1,10 +1,17 @@
-use crate::runtime::task::{self, JoinHandle, Task};
-use crate::runtime::{Driver, Handle};
+use crate::runtime::driver::Unpark;
+use crate::runtime::task::{self, JoinHandle, Remote, Task};
+use crate::runtime::{Driver, Handle, UnhandledPanic};
 
-use std::cell::RefCell;
+use slab::Slab;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
 use std::future::Future;
-use std::task::Waker;
+use std::pin::pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Instant;
 
 pub(crate) struct Scheduler {
     /// Queue of tasks scheduled to run
@@ -12,55 +19,229 @@ pub(crate) struct Scheduler {
 
     /// Current task
     current: RefCell<Option<Task>>,
+
+    /// Tasks spawned on the runtime that have not completed yet
+    owned: RefCell<Slab<Task>>,
+
+    /// Set once the runtime is shutting down
+    is_shutdown: Cell<bool>,
+
+    /// Number of tasks polled before checking for I/O events and timers
+    event_interval: u32,
+
+    /// How to react to a spawned task panicking
+    unhandled_panic: UnhandledPanic,
+
+    /// Set when a task panicked and the runtime must shut down
+    panicked: Cell<bool>,
+
+    /// Receives tasks woken on other threads
+    remote: Arc<Remote>,
 }
 
-const INITIAL_QUEUE_CAPACITY: usize = 256;
+/// Wakes the future passed to `block_on`, which is not a spawned task.
+struct RootWaker {
+    woken: AtomicBool,
+
+    /// Interrupts the driver on wakes from other threads
+    remote: Arc<Remote>,
+}
 
 impl Scheduler {
-    pub(crate) fn new() -> Scheduler {
+    pub(crate) fn new(
+        queue_capacity: usize,
+        event_interval: u32,
+        unhandled_panic: UnhandledPanic,
+        unpark: Unpark,
+    ) -> Scheduler {
         Scheduler {
-            queue: RefCell::new(VecDeque::with_capacity(INITIAL_QUEUE_CAPACITY)),
+            queue: RefCell::new(VecDeque::with_capacity(queue_capacity)),
             current: RefCell::new(None),
+            owned: RefCell::new(Slab::new()),
+            is_shutdown: Cell::new(false),
+            event_interval,
+            unhandled_panic,
+            panicked: Cell::new(false),
+            remote: Arc::new(Remote::new(unpark)),
         }
     }
 
-    pub(crate) fn run(&self, handle: &Handle, driver: &mut Driver) {
+    /// Run until all tasks have completed, or until `deadline` if set.
+    ///
+    /// Returns `false` if the deadline was reached first.
+    pub(crate) fn run(
+        &self,
+        handle: &Handle,
+        driver: &mut Driver,
+        deadline: Option<Instant>,
+    ) -> bool {
         loop {
-            self.tick();
-            driver.park(handle, self).unwrap();
+            let pending = self.tick();
+            self.check_panicked();
+
+            if self.owned.borrow().is_empty() {
+                return true;
+            }
+
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        return false;
+                    }
+
+                    Some(deadline - now)
+                }
+                None => None,
+            };
+
+            if pending {
+                driver.poll(handle, self).unwrap();
+            } else {
+                driver.park(handle, self, timeout).unwrap();
+            }
         }
     }
 
-    pub(crate) fn tick(&self) {
+    pub(crate) fn block_on<T: Future>(
+        &self,
+        handle: &Handle,
+        driver: &mut Driver,
+        future: T,
+    ) -> T::Output {
+        self.check_panicked();
+
+        let mut future = pin!(future);
+
+        // The root future is polled on the first iteration
+        let root = Arc::new(RootWaker {
+            woken: AtomicBool::new(true),
+            remote: self.remote.clone(),
+        });
+        let waker = Waker::from(root.clone());
+        let mut cx = Context::from_waker(&waker);
+
         loop {
+            if root.woken.swap(false, Ordering::Acquire) {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                    return output;
+                }
+            }
+
+            let pending = self.tick();
+            self.check_panicked();
+
+            // Only block on the driver if no task is left to run and running
+            // tasks did not wake the root future.
+            if pending || root.woken.load(Ordering::Acquire) {
+                driver.poll(handle, self).unwrap();
+            } else {
+                driver.park(handle, self, None).unwrap();
+            }
+        }
+    }
+
+    /// Run up to `event_interval` scheduled tasks
+    ///
+    /// Returns `true` if tasks are still scheduled afterwards.
+    pub(crate) fn tick(&self) -> bool {
+        self.remote.drain(self);
+
+        for _ in 0..self.event_interval {
+            if self.panicked.get() {
+                return false;
+            }
+
             let task = match self.next_scheduled_task() {
                 Some(task) => task,
-                None => return,
+                None => return false,
             };
 
             self.run_task(task);
         }
+
+        !self.queue.borrow().is_empty()
     }
 
-    pub(crate) fn spawn<T>(&self, task: T) -> JoinHandle<T::Output>
+    /// Called when a spawned task panicked
+    pub(crate) fn unhandled_panic(&self) {
+        if self.unhandled_panic == UnhandledPanic::ShutdownRuntime {
+            self.panicked.set(true);
+        }
+    }
+
+    /// Cancel all tasks, dropping their futures.
+    ///
+    /// Tasks spawned or woken from then on are cancelled or dropped right
+    /// away.
+    pub(crate) fn shutdown(&self) {
+        self.is_shutdown.set(true);
+
+        let owned: Vec<Task> = self.owned.borrow_mut().drain().collect();
+
+        for task in owned {
+            task.shutdown(self);
+        }
+
+        // Release the references held by the run queue one at a time, as
+        // freeing a task may drop its output and run arbitrary code.
+        loop {
+            let task = self.queue.borrow_mut().pop_front();
+
+            match task {
+                Some(task) => drop(task),
+                None => break,
+            }
+        }
+
+        // Releases tasks woken or freed on other threads
+        self.remote.drain(self);
+    }
+
+    #[track_caller]
+    fn check_panicked(&self) {
+        if self.panicked.get() {
+            panic!("a spawned task panicked and the runtime is configured to shut down on unhandled panic");
+        }
+    }
+
+    pub(crate) fn spawn<T>(self: &Rc<Self>, task: T) -> JoinHandle<T::Output>
     where
         T: Future + 'static,
         T::Output: 'static,
     {
         // Create the task harness
-        let (task, handle) = task::spawn(task);
+        let (task, handle) = task::spawn(task, Rc::downgrade(self), self.remote.clone());
+
+        if self.is_shutdown.get() {
+            task.shutdown(self);
+            return handle;
+        }
+
+        // Track the task until it completes
+        let key = self.owned.borrow_mut().insert(task.clone());
+        task.header().set_owned_key(key);
 
         // Schedule the task for execution
-        self.queue.borrow_mut().push_back(task);
+        self.schedule(task);
 
         // Return the join handle
         handle
     }
 
     /// Schedule a task for execution
+    ///
+    /// Does nothing if the task is already in the run queue, or if the
+    /// runtime is shutting down.
     pub(crate) fn schedule(&self, task: Task) {
-        self.queue.borrow_mut().push_back(task);
-        // self.run_task(task);
+        if self.is_shutdown.get() {
+            return;
+        }
+
+        if task.header().set_queued() {
+            self.queue.borrow_mut().push_back(task);
+        }
     }
 
     /// Returns the `Task` representing the waker
@@ -88,14 +269,29 @@ impl Scheduler {
     fn run_task(&self, task: Task) {
         self.set_current(&task);
 
-        task.poll(self);
+        let complete = task.poll(self);
 
         self.unset_current();
+
+        if complete {
+            self.release(&task);
+        }
+    }
+
+    /// Stop tracking a completed task
+    fn release(&self, task: &Task) {
+        // Freed outside of the borrow, in case it was the last reference
+        let owned = self.owned.borrow_mut().remove(task.header().owned_key());
+        drop(owned);
     }
 
     /// Return the next scheduled task
     fn next_scheduled_task(&self) -> Option<Task> {
-        self.queue.borrow_mut().pop_front()
+        let task = self.queue.borrow_mut().pop_front()?;
+
+        // Wakes from here on must queue the task again
+        task.header().unset_queued();
+        Some(task)
     }
 
     /// Set the currently running task
@@ -107,3 +303,19 @@ impl Scheduler {
         *self.current.borrow_mut() = None;
     }
 }
+
+impl Wake for RootWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+
+        // On the runtime's thread, the flag is checked before the driver
+        // blocks
+        if !self.remote.is_current_thread() {
+            self.remote.unpark();
+        }
+    }
+}
@@ -1,9 +1,21 @@
+mod abort;
+pub use abort::AbortHandle;
+
+mod error;
+pub use error::JoinError;
+
 mod harness;
 use harness::Harness;
 
 mod header;
 use header::Header;
 
+mod join;
+pub use join::JoinHandle;
+
+mod remote;
+pub(crate) use remote::Remote;
+
 mod vtable;
 use vtable::VTable;
 
@@ -13,34 +25,45 @@ use crate::runtime::Scheduler;
 
 use std::future::Future;
 use std::ptr::NonNull;
+use std::rc::Weak;
+use std::sync::Arc;
 use std::task::RawWaker;
 
-pub struct JoinHandle<T> {
-    _p: std::marker::PhantomData<T>,
-}
-
 pub(crate) struct Task {
     header: NonNull<Header>,
 }
 
-pub(crate) fn spawn<T: Future>(future: T) -> (Task, JoinHandle<T::Output>) {
-    let header = Header::new::<T>();
+pub(crate) fn spawn<T: Future>(
+    future: T,
+    scheduler: Weak<Scheduler>,
+    remote: Arc<Remote>,
+) -> (Task, JoinHandle<T::Output>) {
+    let header = Header::new::<T>(scheduler, remote);
 
     let harness = Box::new(Harness::new(header, future));
     let harness = Box::into_raw(harness);
     let header = unsafe { NonNull::new_unchecked(harness as *mut Header) };
 
     let task = Task { header };
-    let handle = JoinHandle {
-        _p: std::marker::PhantomData,
-    };
+    let handle = JoinHandle::new(header);
 
     (task, handle)
 }
 
 impl Task {
-    pub(crate) fn poll(&self, scheduler: &Scheduler) {
-        self.header().poll(scheduler);
+    /// Create a `Task` from an owned reference to the task.
+    pub(crate) unsafe fn from_raw(header: NonNull<Header>) -> Task {
+        Task { header }
+    }
+
+    /// Returns `true` if the task completed during this poll.
+    pub(crate) fn poll(&self, scheduler: &Scheduler) -> bool {
+        self.header().poll(scheduler)
+    }
+
+    /// Cancel the task, dropping its future right away.
+    pub(crate) fn shutdown(&self, scheduler: &Scheduler) {
+        self.header().shutdown(scheduler);
     }
 
     /// Return the raw waker for the this task
@@ -48,14 +71,15 @@ impl Task {
         self.header().raw_waker()
     }
 
-    fn header(&self) -> &Header {
+    pub(crate) fn header(&self) -> &Header {
         unsafe { self.header.as_ref() }
     }
 }
 
 impl Clone for Task {
     fn clone(&self) -> Task {
-        // TODO: Ref inc
+        self.header().ref_inc();
+
         Task {
             header: self.header,
         }
@@ -64,6 +88,6 @@ impl Clone for Task {
 
 impl Drop for Task {
     fn drop(&mut self) {
-        // TODO: don't leak
+        unsafe { Header::ref_dec(self.header) }
     }
 }
@@ -0,0 +1,51 @@
+use crate::runtime::task::Header;
+
+use std::fmt;
+use std::ptr::NonNull;
+
+/// An owned permission to abort a spawned task, without awaiting its
+/// completion.
+///
+/// Unlike a `JoinHandle`, an `AbortHandle` can be cloned.
+pub struct AbortHandle {
+    header: NonNull<Header>,
+}
+
+impl AbortHandle {
+    /// The caller must have incremented the task reference count.
+    pub(super) fn new(header: NonNull<Header>) -> AbortHandle {
+        AbortHandle { header }
+    }
+
+    /// Abort the task associated with the handle.
+    ///
+    /// The task's future is dropped the next time it is scheduled and
+    /// awaiting its `JoinHandle` returns `JoinError::Cancelled`. Aborting a
+    /// task that already completed has no effect.
+    pub fn abort(&self) {
+        self.header().cancel();
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { self.header.as_ref() }
+    }
+}
+
+impl Clone for AbortHandle {
+    fn clone(&self) -> AbortHandle {
+        self.header().ref_inc();
+        AbortHandle::new(self.header)
+    }
+}
+
+impl Drop for AbortHandle {
+    fn drop(&mut self) {
+        unsafe { Header::ref_dec(self.header) }
+    }
+}
+
+impl fmt::Debug for AbortHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AbortHandle").finish()
+    }
+}
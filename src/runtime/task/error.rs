@@ -0,0 +1,82 @@
+use std::any::Any;
+use std::fmt;
+
+/// Task failed to execute to completion.
+pub enum JoinError {
+    /// The task was cancelled before it completed.
+    Cancelled,
+
+    /// The task panicked, the payload is the value the panic was invoked
+    /// with.
+    Panic(Box<dyn Any + Send + 'static>),
+}
+
+impl JoinError {
+    /// Returns true if the error was caused by the task being cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, JoinError::Cancelled)
+    }
+
+    /// Returns true if the error was caused by the task panicking.
+    pub fn is_panic(&self) -> bool {
+        matches!(self, JoinError::Panic(_))
+    }
+
+    /// Consumes the join error, returning the object with which the task
+    /// panicked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the error does not represent the underlying task
+    /// terminating with a panic. Use `is_panic` to check the error reason or
+    /// `try_into_panic` for a variant that does not panic.
+    #[track_caller]
+    pub fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        self.try_into_panic()
+            .expect("`JoinError` reason is not a panic.")
+    }
+
+    /// Consumes the join error, returning the object with which the task
+    /// panicked if the task terminated due to a panic. Otherwise, `self` is
+    /// returned.
+    pub fn try_into_panic(self) -> Result<Box<dyn Any + Send + 'static>, JoinError> {
+        match self {
+            JoinError::Panic(payload) => Ok(payload),
+            _ => Err(self),
+        }
+    }
+
+    /// Returns the panic message, if the payload is a string.
+    fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+        payload
+            .downcast_ref::<&'static str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Cancelled => write!(f, "task was cancelled"),
+            JoinError::Panic(payload) => match JoinError::panic_message(&**payload) {
+                Some(message) => write!(f, "task panicked with message {:?}", message),
+                None => write!(f, "task panicked"),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Cancelled => write!(f, "JoinError::Cancelled"),
+            JoinError::Panic(payload) => match JoinError::panic_message(&**payload) {
+                Some(message) => write!(f, "JoinError::Panic({:?}, ...)", message),
+                None => write!(f, "JoinError::Panic(...)"),
+            },
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
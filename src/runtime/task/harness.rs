@@ -1,25 +1,30 @@
 use crate::runtime::task::waker::waker_ref;
-use crate::runtime::task::Header;
+use crate::runtime::task::{Header, JoinError};
 use crate::runtime::Scheduler;
 
 use std::cell::RefCell;
 use std::future::Future;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 
 /// Task harness
 #[repr(C)]
 pub(crate) struct Harness<T: Future> {
     header: Header,
     state: RefCell<State<T>>,
+
+    /// Waker of the task waiting on the `JoinHandle`, if any
+    join_waker: RefCell<Option<Waker>>,
 }
 
 enum State<T: Future> {
     // The future is not yet complete
     InProgress(T),
 
-    // The future is complete and we have the output
-    Complete(T::Output),
+    // The future is complete, or was cancelled, and we have the output
+    Complete(Result<T::Output, JoinError>),
 
     // The future output has been consumed.
     Joined,
@@ -30,6 +35,7 @@ impl<T: Future> Harness<T> {
         Harness {
             header,
             state: RefCell::new(State::InProgress(future)),
+            join_waker: RefCell::new(None),
         }
     }
 
@@ -37,9 +43,14 @@ impl<T: Future> Harness<T> {
         &*(header as *const _ as *const Harness<T>)
     }
 
-    pub fn poll(&self, scheduler: &Scheduler) {
+    /// Returns `true` if the task completed during this poll.
+    pub fn poll(&self, scheduler: &Scheduler) -> bool {
         use State::*;
 
+        if self.header.is_cancelled() {
+            return self.cancel(scheduler);
+        }
+
         // Build the waker
         let waker = waker_ref(&self.header);
         let mut cx = Context::from_waker(&waker);
@@ -48,18 +59,115 @@ impl<T: Future> Harness<T> {
 
         let future = match &mut *state {
             InProgress(future) => future,
-            _ => panic!("invalid task state"),
+            // A waker outlived the future, there is nothing left to poll
+            _ => return false,
         };
 
         // Safety: we don't move the future until it is dropped.
         let future = unsafe { Pin::new_unchecked(future) };
 
-        // Poll the future
-        match future.poll(&mut cx) {
-            Poll::Ready(_) => {
-                *state = Joined;
+        // Poll the future, containing any panic to this task
+        let res = panic::catch_unwind(AssertUnwindSafe(|| future.poll(&mut cx)));
+
+        match res {
+            Ok(Poll::Ready(output)) => {
+                let future = mem::replace(&mut *state, Complete(Ok(output)));
+                drop(state);
+
+                // Dropped outside of the borrow as the future's destructor
+                // may run arbitrary code.
+                let res = panic::catch_unwind(AssertUnwindSafe(|| drop(future)));
+
+                if let Err(payload) = res {
+                    // The task fails with the panic, discarding its output
+                    let output = mem::replace(
+                        &mut *self.state.borrow_mut(),
+                        Complete(Err(JoinError::Panic(payload))),
+                    );
+                    let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(output)));
+
+                    self.notify_join();
+                    scheduler.unhandled_panic();
+                } else {
+                    self.notify_join();
+                }
+
+                true
+            }
+            Ok(Poll::Pending) => false,
+            Err(payload) => {
+                let future = mem::replace(&mut *state, Complete(Err(JoinError::Panic(payload))));
+                drop(state);
+
+                // The task already failed with the first panic, ignore any
+                // panic raised while dropping the future.
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| drop(future)));
+
+                self.notify_join();
+                scheduler.unhandled_panic();
+                true
+            }
+        }
+    }
+
+    /// Drop the future, completing the task with `JoinError::Cancelled`.
+    ///
+    /// Returns `false` if the task had already completed.
+    fn cancel(&self, scheduler: &Scheduler) -> bool {
+        use State::*;
+
+        let mut state = self.state.borrow_mut();
+
+        if !matches!(*state, InProgress(_)) {
+            return false;
+        }
+
+        let future = mem::replace(&mut *state, Complete(Err(JoinError::Cancelled)));
+        drop(state);
+
+        // Dropped outside of the borrow as the future's destructor may run
+        // arbitrary code.
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| drop(future))) {
+            *self.state.borrow_mut() = Complete(Err(JoinError::Panic(payload)));
+            scheduler.unhandled_panic();
+        }
+
+        self.notify_join();
+        true
+    }
+
+    fn notify_join(&self) {
+        let maybe_waker = self.join_waker.borrow_mut().take();
+        if let Some(waker) = maybe_waker {
+            waker.wake();
+        }
+    }
+
+    /// Take the output if the task has completed, otherwise store the
+    /// waker to notify once it does.
+    pub(crate) fn try_read_output(
+        &self,
+        dst: &mut Poll<Result<T::Output, JoinError>>,
+        waker: &Waker,
+    ) {
+        use State::*;
+
+        let mut state = self.state.borrow_mut();
+
+        match &*state {
+            InProgress(_) => {
+                let mut join_waker = self.join_waker.borrow_mut();
+
+                match &*join_waker {
+                    Some(existing) if existing.will_wake(waker) => {}
+                    _ => *join_waker = Some(waker.clone()),
+                }
             }
-            Poll::Pending => {}
+            Complete(_) => match mem::replace(&mut *state, Joined) {
+                Complete(output) => *dst = Poll::Ready(output),
+                _ => unreachable!(),
+            },
+            Joined => panic!("JoinHandle polled after completion"),
         }
     }
 }
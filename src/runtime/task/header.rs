@@ -1,26 +1,180 @@
-use crate::runtime::task::VTable;
+use crate::runtime::task::{Remote, Task, VTable};
 use crate::runtime::Scheduler;
 
+use std::cell::Cell;
 use std::future::Future;
-use std::task::RawWaker;
+use std::ptr::NonNull;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{RawWaker, Waker};
 
 #[repr(C)]
 pub(crate) struct Header {
     /// Dynamic dispatch to future-specific functions.
     vtable: &'static VTable,
+
+    /// Number of `Task`, `JoinHandle` and waker references to the task.
+    ///
+    /// Atomic as wakers may be cloned and dropped on any thread. All other
+    /// fields are only accessed by the thread driving the runtime.
+    ref_count: AtomicUsize,
+
+    /// True while the task is in the scheduler's run queue.
+    queued: Cell<bool>,
+
+    /// True once the task has been aborted.
+    cancelled: Cell<bool>,
+
+    /// Key of the task in the scheduler's list of owned tasks.
+    owned_key: Cell<usize>,
+
+    /// Scheduler the task is pushed to when woken.
+    scheduler: Weak<Scheduler>,
+
+    /// Hands the task to the scheduler's thread when woken or released on
+    /// another thread.
+    remote: Arc<Remote>,
 }
 
 impl Header {
-    pub(crate) fn new<T: Future>() -> Header {
+    /// The task starts out referenced by its `Task` and its `JoinHandle`.
+    pub(crate) fn new<T: Future>(scheduler: Weak<Scheduler>, remote: Arc<Remote>) -> Header {
         Header {
             vtable: VTable::for_future::<T>(),
+            ref_count: AtomicUsize::new(2),
+            queued: Cell::new(false),
+            cancelled: Cell::new(false),
+            owned_key: Cell::new(0),
+            scheduler,
+            remote,
+        }
+    }
+
+    pub(crate) fn ref_inc(&self) {
+        // A new reference is created from an existing one, no
+        // synchronization is needed, as for `Arc`
+        self.ref_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Release a reference to the task, freeing it if it was the last one.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a live task and the caller must own one of its
+    /// references, which may not be used after this call.
+    pub(crate) unsafe fn ref_dec(ptr: NonNull<Header>) {
+        let header = ptr.as_ref();
+
+        if header.ref_count.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+
+        // Synchronize with the release of the other references
+        atomic::fence(Ordering::Acquire);
+
+        // The future and its output may only be dropped on the thread
+        // driving the runtime
+        if header.remote.is_current_thread() {
+            Header::dealloc(ptr);
+        } else {
+            header.remote.dealloc(ptr);
+        }
+    }
+
+    /// Drop the future or output and free the task memory.
+    ///
+    /// # Safety
+    ///
+    /// The task's reference count must have dropped to zero.
+    pub(crate) unsafe fn dealloc(ptr: NonNull<Header>) {
+        (ptr.as_ref().vtable.dealloc)(ptr);
+    }
+
+    /// Mark the task as queued, returning `false` if it already was.
+    pub(crate) fn set_queued(&self) -> bool {
+        !self.queued.replace(true)
+    }
+
+    pub(crate) fn unset_queued(&self) {
+        self.queued.set(false);
+    }
+
+    pub(crate) fn owned_key(&self) -> usize {
+        self.owned_key.get()
+    }
+
+    pub(crate) fn set_owned_key(&self, key: usize) {
+        self.owned_key.set(key);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+
+    /// Mark the task as cancelled and schedule it so its future is dropped.
+    pub(crate) fn cancel(&self) {
+        if self.cancelled.replace(true) {
+            return;
+        }
+
+        self.ref_inc();
+        unsafe { Header::schedule(NonNull::from(self)) }
+    }
+
+    /// Cancel the task, dropping its future right away.
+    ///
+    /// Returns `true` if the task completed because of this call.
+    pub(crate) fn shutdown(&self, scheduler: &Scheduler) -> bool {
+        self.cancelled.set(true);
+        self.poll(scheduler)
+    }
+
+    /// Returns the scheduler the task was spawned on, if the runtime is
+    /// still alive.
+    pub(crate) fn scheduler(&self) -> Option<Rc<Scheduler>> {
+        self.scheduler.upgrade()
+    }
+
+    /// Push the task onto its scheduler's run queue, consuming the
+    /// reference.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own one of the task's references, which may not be
+    /// used after this call.
+    pub(crate) unsafe fn schedule(ptr: NonNull<Header>) {
+        let remote = &ptr.as_ref().remote;
+
+        if !remote.is_current_thread() {
+            remote.schedule(ptr);
+            return;
+        }
+
+        let task = Task::from_raw(ptr);
+
+        // If the runtime is gone, there is nothing left to run the task.
+        if let Some(scheduler) = task.header().scheduler() {
+            scheduler.schedule(task);
         }
     }
 
-    pub(crate) fn poll(&self, scheduler: &Scheduler) {
+    /// Returns `true` if the task completed during this poll.
+    pub(crate) fn poll(&self, scheduler: &Scheduler) -> bool {
         (self.vtable.poll)(scheduler, self)
     }
 
+    /// Read the task output into `dst` if the task has completed, otherwise
+    /// register `waker` to be notified on completion.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must point to a `Poll<Result<T::Output, JoinError>>` where `T`
+    /// is the future the task was spawned with.
+    pub(crate) unsafe fn try_read_output(&self, dst: *mut (), waker: &Waker) {
+        (self.vtable.try_read_output)(self, dst, waker)
+    }
+
     pub(crate) fn raw_waker(&self) -> RawWaker {
         let ptr = self as *const _ as *const ();
         RawWaker::new(ptr, self.vtable.waker_ref)
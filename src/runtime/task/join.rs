@@ -0,0 +1,77 @@
+use crate::runtime::task::{AbortHandle, Header, JoinError};
+
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::task::{Context, Poll};
+
+/// An owned permission to join on a task (await its termination).
+///
+/// Awaiting a `JoinHandle` yields the output of the spawned task once it
+/// completes.
+pub struct JoinHandle<T> {
+    header: NonNull<Header>,
+    _p: PhantomData<T>,
+}
+
+impl<T> JoinHandle<T> {
+    pub(super) fn new(header: NonNull<Header>) -> JoinHandle<T> {
+        JoinHandle {
+            header,
+            _p: PhantomData,
+        }
+    }
+
+    /// Abort the task associated with the handle.
+    ///
+    /// The task's future is dropped the next time it is scheduled and
+    /// awaiting this `JoinHandle` returns `JoinError::Cancelled`. Aborting a
+    /// task that already completed has no effect.
+    pub fn abort(&self) {
+        self.header().cancel();
+    }
+
+    /// Returns a new `AbortHandle` that can be used to remotely abort this
+    /// task.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.header().ref_inc();
+        AbortHandle::new(self.header)
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { self.header.as_ref() }
+    }
+}
+
+impl<T> Unpin for JoinHandle<T> {}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut ret = Poll::Pending;
+
+        // Safety: the type of `ret` matches the output type of the future
+        // the task was spawned with.
+        unsafe {
+            self.header()
+                .try_read_output(&mut ret as *mut _ as *mut (), cx.waker());
+        }
+
+        ret
+    }
+}
+
+impl<T> Drop for JoinHandle<T> {
+    fn drop(&mut self) {
+        unsafe { Header::ref_dec(self.header) }
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle").finish()
+    }
+}
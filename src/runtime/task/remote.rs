@@ -0,0 +1,108 @@
+use crate::runtime::driver::Unpark;
+use crate::runtime::task::{Header, Task};
+use crate::runtime::Scheduler;
+
+use std::mem;
+use std::ptr::NonNull;
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+/// Hands tasks woken or released on other threads over to the thread
+/// driving the runtime, the only one allowed to touch them.
+///
+/// Operations handed over once the runtime is gone are never processed, the
+/// tasks are leaked.
+pub(crate) struct Remote {
+    /// Thread driving the runtime
+    thread: ThreadId,
+
+    /// Operations waiting for the thread driving the runtime
+    queue: Mutex<Vec<Op>>,
+
+    /// Interrupts the driver so the operations are processed
+    unpark: Unpark,
+}
+
+enum Op {
+    /// Schedule the task, consuming one of its references
+    Schedule(NonNull<Header>),
+
+    /// Free the task, whose last reference was released
+    Dealloc(NonNull<Header>),
+}
+
+// Safety: the task pointers are only dereferenced by the thread driving the
+// runtime.
+unsafe impl Send for Op {}
+
+impl Remote {
+    /// Must be called on the thread driving the runtime.
+    pub(crate) fn new(unpark: Unpark) -> Remote {
+        Remote {
+            thread: thread::current().id(),
+            queue: Mutex::new(Vec::new()),
+            unpark,
+        }
+    }
+
+    pub(crate) fn is_current_thread(&self) -> bool {
+        thread::current().id() == self.thread
+    }
+
+    /// Schedule the task from another thread, waking the driver.
+    ///
+    /// # Safety
+    ///
+    /// The caller must own one of the task's references, which is handed
+    /// over.
+    pub(super) unsafe fn schedule(&self, ptr: NonNull<Header>) {
+        self.push(Op::Schedule(ptr));
+        self.unpark();
+    }
+
+    /// Free the task from another thread.
+    ///
+    /// The driver is not woken, the memory is reclaimed on its next tick.
+    ///
+    /// # Safety
+    ///
+    /// The task's reference count must have dropped to zero.
+    pub(super) unsafe fn dealloc(&self, ptr: NonNull<Header>) {
+        self.push(Op::Dealloc(ptr));
+    }
+
+    /// Interrupt the driver if it is blocked waiting for events.
+    pub(crate) fn unpark(&self) {
+        self.unpark.unpark();
+    }
+
+    /// Process the operations handed over by other threads.
+    ///
+    /// Must be called on the thread driving the runtime.
+    pub(crate) fn drain(&self, scheduler: &Scheduler) {
+        debug_assert!(self.is_current_thread());
+
+        let ops = {
+            let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+
+            if queue.is_empty() {
+                return;
+            }
+
+            mem::take(&mut *queue)
+        };
+
+        for op in ops {
+            match op {
+                Op::Schedule(ptr) => scheduler.schedule(unsafe { Task::from_raw(ptr) }),
+                Op::Dealloc(ptr) => unsafe { Header::dealloc(ptr) },
+            }
+        }
+    }
+
+    fn push(&self, op: Op) {
+        // The queue is left consistent by a panicking holder
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        queue.push(op);
+    }
+}
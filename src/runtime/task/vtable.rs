@@ -1,11 +1,19 @@
+use crate::runtime::task::JoinError;
 use crate::runtime::{task, Scheduler};
 
 use std::future::Future;
-use std::task::{RawWaker, RawWakerVTable};
+use std::ptr::NonNull;
+use std::task::{Poll, RawWaker, RawWakerVTable, Waker};
 
 pub(crate) struct VTable {
-    /// Poll the future
-    pub(super) poll: fn(scheduler: &Scheduler, &task::Header),
+    /// Poll the future, returning `true` if the task completed
+    pub(super) poll: fn(scheduler: &Scheduler, &task::Header) -> bool,
+
+    /// Read the task output, if complete
+    pub(super) try_read_output: unsafe fn(&task::Header, *mut (), &Waker),
+
+    /// Drop the future or output and free the task memory
+    pub(super) dealloc: unsafe fn(NonNull<task::Header>),
 
     /// Waker ref VTable
     pub(super) waker_ref: &'static RawWakerVTable,
@@ -15,6 +23,8 @@ impl VTable {
     pub(crate) fn for_future<T: Future>() -> &'static VTable {
         &VTable {
             poll: poll::<T>,
+            try_read_output: try_read_output::<T>,
+            dealloc: dealloc::<T>,
             waker_ref: &RawWakerVTable::new(
                 clone_waker::<T>,
                 wake_by_val::<T>,
@@ -25,29 +35,41 @@ impl VTable {
     }
 }
 
-fn poll<T: Future>(scheduler: &Scheduler, task: &task::Header) {
-    unsafe { task::Harness::<T>::from_header_ref(task) }.poll(scheduler);
+fn poll<T: Future>(scheduler: &Scheduler, task: &task::Header) -> bool {
+    unsafe { task::Harness::<T>::from_header_ref(task) }.poll(scheduler)
+}
+
+unsafe fn try_read_output<T: Future>(task: &task::Header, dst: *mut (), waker: &Waker) {
+    let dst = &mut *(dst as *mut Poll<Result<T::Output, JoinError>>);
+    task::Harness::<T>::from_header_ref(task).try_read_output(dst, waker);
+}
+
+unsafe fn dealloc<T: Future>(task: NonNull<task::Header>) {
+    drop(Box::from_raw(task.as_ptr() as *mut task::Harness<T>));
 }
 
 unsafe fn clone_waker<T>(ptr: *const ()) -> RawWaker
 where
     T: Future,
 {
-    todo!()
+    let header = &*(ptr as *const task::Header);
+    header.ref_inc();
+    header.raw_waker()
 }
 
 unsafe fn drop_waker<T>(ptr: *const ())
 where
     T: Future,
 {
-    todo!()
+    task::Header::ref_dec(NonNull::new_unchecked(ptr as *mut task::Header));
 }
 
 unsafe fn wake_by_val<T>(ptr: *const ())
 where
     T: Future,
 {
-    todo!()
+    // The waker's reference is handed to the scheduler
+    task::Header::schedule(NonNull::new_unchecked(ptr as *mut task::Header));
 }
 
 // Wake without consuming the waker
@@ -55,5 +77,7 @@ unsafe fn wake_by_ref<T>(ptr: *const ())
 where
     T: Future,
 {
-    todo!()
+    let header = &*(ptr as *const task::Header);
+    header.ref_inc();
+    task::Header::schedule(NonNull::new_unchecked(ptr as *mut task::Header));
 }
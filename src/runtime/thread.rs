@@ -0,0 +1,27 @@
+use std::ffi::{CStr, CString};
+
+/// Names the current thread, returning its previous name.
+#[cfg(target_os = "linux")]
+pub(crate) fn set_name(name: &CStr) -> Option<CString> {
+    // Names are at most 16 bytes, including the nul terminator
+    let mut prev = [0u8; 16];
+
+    let prev = unsafe {
+        if libc::prctl(libc::PR_GET_NAME, prev.as_mut_ptr()) != 0 {
+            return None;
+        }
+
+        CStr::from_ptr(prev.as_ptr().cast()).to_owned()
+    };
+
+    unsafe {
+        libc::prctl(libc::PR_SET_NAME, name.as_ptr());
+    }
+
+    Some(prev)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn set_name(_name: &CStr) -> Option<CString> {
+    None
+}
@@ -0,0 +1,187 @@
+mod clock;
+pub(crate) use clock::Clock;
+
+mod wheel;
+use wheel::{Location, Wheel};
+
+use crate::time::Instant;
+
+use slab::Slab;
+use std::cell::RefCell;
+use std::task::{Poll, Waker};
+use std::time::Duration;
+
+/// Tracks timers for the runtime
+pub(crate) struct Handle {
+    /// Source of time for the timers
+    clock: Clock,
+
+    /// Instant of tick 0
+    start: Instant,
+
+    inner: RefCell<Inner>,
+}
+
+struct Inner {
+    /// Registered timers, keyed by the slab key
+    entries: Slab<Entry>,
+
+    /// Orders timers by deadline
+    wheel: Wheel,
+}
+
+struct Entry {
+    /// Tick at which the timer fires
+    when: u64,
+
+    /// Location in the wheel, unless the timer already fired
+    location: Option<Location>,
+
+    /// Task waiting on the timer
+    waker: Option<Waker>,
+}
+
+const INITIAL_ENTRIES_CAPACITY: usize = 256;
+
+impl Handle {
+    pub(crate) fn new() -> Handle {
+        let clock = Clock::new();
+
+        Handle {
+            start: clock.start(),
+            clock,
+            inner: RefCell::new(Inner {
+                entries: Slab::with_capacity(INITIAL_ENTRIES_CAPACITY),
+                wheel: Wheel::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Register a timer firing at `deadline`, returning its key.
+    pub(crate) fn register(&self, deadline: Instant) -> usize {
+        let when = self.deadline_to_tick(deadline);
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+
+        let entry = inner.entries.vacant_entry();
+        let key = entry.key();
+
+        entry.insert(Entry {
+            when,
+            location: inner.wheel.insert(when, key),
+            waker: None,
+        });
+
+        key
+    }
+
+    /// Set a new deadline for the timer, which fires again if it already did.
+    pub(crate) fn reset(&self, key: usize, deadline: Instant) {
+        let when = self.deadline_to_tick(deadline);
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        let entry = &mut inner.entries[key];
+
+        if let Some(location) = entry.location.take() {
+            inner.wheel.remove(location, key);
+        }
+
+        entry.when = when;
+        entry.location = inner.wheel.insert(when, key);
+    }
+
+    pub(crate) fn deregister(&self, key: usize) {
+        let mut inner = self.inner.borrow_mut();
+        let entry = inner.entries.remove(key);
+
+        if let Some(location) = entry.location {
+            inner.wheel.remove(location, key);
+        }
+    }
+
+    /// Returns true if the timer fired
+    pub(crate) fn is_elapsed(&self, key: usize) -> bool {
+        self.inner.borrow().entries[key].location.is_none()
+    }
+
+    /// Returns `Ready` if the timer fired, otherwise stores the waker to
+    /// notify once it does.
+    pub(crate) fn poll_elapsed(&self, key: usize, waker: &Waker) -> Poll<()> {
+        let mut inner = self.inner.borrow_mut();
+        let entry = &mut inner.entries[key];
+
+        if entry.location.is_none() {
+            return Poll::Ready(());
+        }
+
+        match &entry.waker {
+            Some(existing) if existing.will_wake(waker) => {}
+            _ => entry.waker = Some(waker.clone()),
+        }
+
+        Poll::Pending
+    }
+
+    /// Returns the instant at which the next timer fires
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let inner = self.inner.borrow();
+        let expiration = inner.wheel.next_expiration()?;
+
+        Some(self.tick_to_instant(expiration.deadline))
+    }
+
+    /// Fire all timers whose deadline is at or before `now`.
+    pub(crate) fn process(&self, now: Instant) {
+        let now = self.instant_to_tick(now);
+        let mut wakers = vec![];
+
+        {
+            let mut inner = self.inner.borrow_mut();
+            let inner = &mut *inner;
+
+            while let Some(expiration) = inner.wheel.poll(now) {
+                for key in inner.wheel.take_slot(expiration) {
+                    let entry = &mut inner.entries[key];
+
+                    // Moves the timer down a level, unless it expired
+                    entry.location = inner.wheel.insert(entry.when, key);
+
+                    if entry.location.is_none() {
+                        wakers.extend(entry.waker.take());
+                    }
+                }
+            }
+
+            inner.wheel.set_elapsed(now);
+        }
+
+        // Woken once the timers are no longer borrowed
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Rounds up, so timers never fire early
+    fn deadline_to_tick(&self, deadline: Instant) -> u64 {
+        let since_start = deadline.saturating_duration_since(self.start);
+        let ms = since_start
+            .checked_add(Duration::from_nanos(999_999))
+            .unwrap_or(since_start)
+            .as_millis();
+
+        ms.try_into().unwrap_or(u64::MAX)
+    }
+
+    fn instant_to_tick(&self, instant: Instant) -> u64 {
+        let ms = instant.saturating_duration_since(self.start).as_millis();
+        ms.try_into().unwrap_or(u64::MAX)
+    }
+
+    fn tick_to_instant(&self, tick: u64) -> Instant {
+        self.start + Duration::from_millis(tick)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::time::Instant;
+
+use std::cell::Cell;
+use std::time::Duration;
+
+/// Source of time for the runtime
+///
+/// Follows the system clock unless paused, after which time only moves
+/// forward when advanced explicitly or when the runtime is idle.
+pub(crate) struct Clock {
+    /// Instant the clock was created at
+    start: std::time::Instant,
+
+    /// Time reported by the clock when it was last paused or resumed
+    base: Cell<std::time::Instant>,
+
+    /// Real time at which the clock was resumed, `None` while paused
+    unfrozen: Cell<Option<std::time::Instant>>,
+}
+
+impl Clock {
+    pub(crate) fn new() -> Clock {
+        let now = std::time::Instant::now();
+
+        Clock {
+            start: now,
+            base: Cell::new(now),
+            unfrozen: Cell::new(Some(now)),
+        }
+    }
+
+    pub(crate) fn now(&self) -> Instant {
+        let mut now = self.base.get();
+
+        if let Some(unfrozen) = self.unfrozen.get() {
+            now += unfrozen.elapsed();
+        }
+
+        Instant::from_std(now)
+    }
+
+    /// Instant the clock was created at
+    pub(crate) fn start(&self) -> Instant {
+        Instant::from_std(self.start)
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.unfrozen.get().is_none()
+    }
+
+    #[track_caller]
+    pub(crate) fn pause(&self) {
+        assert!(!self.is_paused(), "time is already frozen");
+
+        // Round up to a whole millisecond since the start, so paused time
+        // lines up with the ticks of the timer wheel.
+        let elapsed = self.now().into_std() - self.start;
+        let ms = elapsed.as_nanos().div_ceil(1_000_000);
+
+        self.base.set(self.start + Duration::from_millis(ms as u64));
+        self.unfrozen.set(None);
+    }
+
+    #[track_caller]
+    pub(crate) fn resume(&self) {
+        assert!(self.is_paused(), "time is not frozen");
+
+        self.unfrozen.set(Some(std::time::Instant::now()));
+    }
+
+    #[track_caller]
+    pub(crate) fn advance(&self, duration: Duration) {
+        assert!(self.is_paused(), "time is not frozen");
+
+        self.base.set(self.base.get() + duration);
+    }
+}
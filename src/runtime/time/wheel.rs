@@ -0,0 +1,397 @@
+//! Hierarchical timer wheel
+//!
+//! Timers are kept in `NUM_LEVELS` levels of `LEVEL_MULT` slots. A slot on
+//! level 0 covers a single tick (one millisecond), a slot on level 1 covers
+//! 64 ticks, and so on. A timer is placed on the lowest level whose range
+//! still covers its deadline, and is moved down a level each time the slot
+//! it sits in is reached, until it expires.
+
+use std::mem;
+
+/// Number of levels
+const NUM_LEVELS: usize = 6;
+
+/// Number of slots per level
+const LEVEL_MULT: usize = 64;
+
+/// Ticks covered by all the levels
+pub(crate) const MAX_DURATION: u64 = (1 << (6 * NUM_LEVELS)) - 1;
+
+pub(crate) struct Wheel {
+    /// Ticks elapsed since the wheel was created
+    elapsed: u64,
+
+    /// Timer levels, from the finest to the coarsest
+    levels: Box<[Level]>,
+}
+
+/// Position of a timer in the wheel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Location {
+    level: usize,
+    slot: usize,
+}
+
+/// The next slot to process
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Expiration {
+    location: Location,
+
+    /// Tick at which the slot is reached
+    pub(crate) deadline: u64,
+}
+
+struct Level {
+    level: usize,
+
+    /// Bit field of the slots holding timers
+    occupied: u64,
+
+    /// Keys of the timers in each slot
+    slots: [Vec<usize>; LEVEL_MULT],
+}
+
+impl Wheel {
+    pub(crate) fn new() -> Wheel {
+        Wheel {
+            elapsed: 0,
+            levels: (0..NUM_LEVELS).map(Level::new).collect(),
+        }
+    }
+
+    /// Insert the timer `key` expiring at tick `when`.
+    ///
+    /// Returns `None` without inserting anything if `when` has already
+    /// elapsed.
+    pub(crate) fn insert(&mut self, when: u64, key: usize) -> Option<Location> {
+        if when <= self.elapsed {
+            return None;
+        }
+
+        // Timers further out than the wheel covers are placed in the last
+        // slot, and inserted again once it is reached.
+        let when = when.min(self.elapsed.saturating_add(MAX_DURATION));
+
+        let level = level_for(self.elapsed, when);
+        let location = self.levels[level].add(when, key);
+
+        Some(location)
+    }
+
+    /// Remove the timer `key` previously inserted at `location`.
+    pub(crate) fn remove(&mut self, location: Location, key: usize) {
+        self.levels[location.level].remove(location.slot, key);
+    }
+
+    /// Returns the next slot to process, if any timer is set.
+    pub(crate) fn next_expiration(&self) -> Option<Expiration> {
+        // Lower levels always expire first
+        self.levels
+            .iter()
+            .find_map(|level| level.next_expiration(self.elapsed))
+    }
+
+    /// Returns the next slot reached at or before `now`, advancing the
+    /// wheel to it.
+    ///
+    /// The timers in the slot must then be taken with `take_slot` and either
+    /// fired or inserted again, which places them on a lower level.
+    pub(crate) fn poll(&mut self, now: u64) -> Option<Expiration> {
+        let expiration = self.next_expiration()?;
+
+        if expiration.deadline > now {
+            return None;
+        }
+
+        self.elapsed = self.elapsed.max(expiration.deadline);
+        Some(expiration)
+    }
+
+    /// Take all timers out of the slot of `expiration`
+    pub(crate) fn take_slot(&mut self, expiration: Expiration) -> Vec<usize> {
+        let Location { level, slot } = expiration.location;
+        self.levels[level].take(slot)
+    }
+
+    /// Advance the wheel to `now`, once all timers up to `now` are processed.
+    pub(crate) fn set_elapsed(&mut self, now: u64) {
+        self.elapsed = self.elapsed.max(now);
+    }
+}
+
+impl Level {
+    fn new(level: usize) -> Level {
+        Level {
+            level,
+            occupied: 0,
+            slots: std::array::from_fn(|_| Vec::new()),
+        }
+    }
+
+    fn add(&mut self, when: u64, key: usize) -> Location {
+        let slot = slot_for(when, self.level);
+
+        self.slots[slot].push(key);
+        self.occupied |= 1 << slot;
+
+        Location {
+            level: self.level,
+            slot,
+        }
+    }
+
+    fn remove(&mut self, slot: usize, key: usize) {
+        let entries = &mut self.slots[slot];
+
+        if let Some(pos) = entries.iter().position(|k| *k == key) {
+            entries.swap_remove(pos);
+        }
+
+        if entries.is_empty() {
+            self.occupied &= !(1 << slot);
+        }
+    }
+
+    fn take(&mut self, slot: usize) -> Vec<usize> {
+        self.occupied &= !(1 << slot);
+        mem::take(&mut self.slots[slot])
+    }
+
+    fn next_expiration(&self, now: u64) -> Option<Expiration> {
+        let slot = self.next_occupied_slot(now)?;
+
+        let level_range = level_range(self.level);
+        let slot_range = slot_range(self.level);
+
+        // Start of the current rotation of this level
+        let level_start = now & !(level_range - 1);
+        let mut deadline = level_start + slot as u64 * slot_range;
+
+        if deadline <= now {
+            // Only happens on the top level, for a slot of the next rotation.
+            deadline += level_range;
+        }
+
+        Some(Expiration {
+            location: Location {
+                level: self.level,
+                slot,
+            },
+            deadline,
+        })
+    }
+
+    fn next_occupied_slot(&self, now: u64) -> Option<usize> {
+        if self.occupied == 0 {
+            return None;
+        }
+
+        // Search starting after the slot `now` is in. Only the top level
+        // may hold timers in that slot, which belong to the next rotation.
+        let start = (now / slot_range(self.level) + 1) as usize % LEVEL_MULT;
+        let occupied = self.occupied.rotate_right(start as u32);
+        let zeros = occupied.trailing_zeros() as usize;
+
+        Some((zeros + start) % LEVEL_MULT)
+    }
+}
+
+/// Level on which a timer expiring at `when` is placed
+fn level_for(elapsed: u64, when: u64) -> usize {
+    const SLOT_MASK: u64 = (1 << 6) - 1;
+
+    // The highest bit that differs between `elapsed` and `when` selects the
+    // level. Timers in the next rotation of the top level stay on it.
+    let masked = (elapsed ^ when | SLOT_MASK).min(MAX_DURATION - 1);
+    let significant = 63 - masked.leading_zeros() as usize;
+
+    significant / 6
+}
+
+fn slot_for(when: u64, level: usize) -> usize {
+    ((when >> (level * 6)) % LEVEL_MULT as u64) as usize
+}
+
+/// Ticks covered by a single slot of `level`
+fn slot_range(level: usize) -> u64 {
+    (LEVEL_MULT as u64).pow(level as u32)
+}
+
+/// Ticks covered by all slots of `level`
+fn level_range(level: usize) -> u64 {
+    LEVEL_MULT as u64 * slot_range(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Process the wheel up to `now` as the time driver does, returning the
+    /// keys of the expired timers in firing order. `whens[key]` is the
+    /// deadline of timer `key`.
+    fn process(wheel: &mut Wheel, whens: &[u64], now: u64) -> Vec<usize> {
+        let mut fired = vec![];
+
+        while let Some(expiration) = wheel.poll(now) {
+            for key in wheel.take_slot(expiration) {
+                if wheel.insert(whens[key], key).is_none() {
+                    fired.push(key);
+                }
+            }
+        }
+
+        wheel.set_elapsed(now);
+        fired
+    }
+
+    #[test]
+    fn level_boundaries() {
+        for (when, level) in [(1, 0), (63, 0), (64, 1), (4095, 1), (4096, 2), (262_144, 3)] {
+            let mut wheel = Wheel::new();
+            let location = wheel.insert(when, 0).unwrap();
+            assert_eq!(location.level, level, "when = {}", when);
+
+            assert!(
+                process(&mut wheel, &[when], when - 1).is_empty(),
+                "when = {}",
+                when
+            );
+            assert_eq!(process(&mut wheel, &[when], when), [0], "when = {}", when);
+            assert!(wheel.next_expiration().is_none());
+        }
+    }
+
+    #[test]
+    fn elapsed_deadline_is_not_inserted() {
+        let mut wheel = Wheel::new();
+        wheel.set_elapsed(100);
+
+        assert!(wheel.insert(100, 0).is_none());
+        assert!(wheel.insert(99, 0).is_none());
+        assert!(wheel.next_expiration().is_none());
+    }
+
+    #[test]
+    fn deadline_relative_to_elapsed() {
+        // Boundaries are crossed relative to the elapsed ticks, not to the
+        // distance from them
+        let mut wheel = Wheel::new();
+        wheel.set_elapsed(60);
+
+        let whens = [62, 65, 4100];
+        for (key, &when) in whens.iter().enumerate() {
+            wheel.insert(when, key).unwrap();
+        }
+
+        assert_eq!(process(&mut wheel, &whens, 64), [0]);
+        assert_eq!(process(&mut wheel, &whens, 4099), [1]);
+        assert_eq!(process(&mut wheel, &whens, 4100), [2]);
+    }
+
+    #[test]
+    fn beyond_max_duration() {
+        let mut wheel = Wheel::new();
+        let whens = [MAX_DURATION + 1_000, MAX_DURATION];
+
+        assert_eq!(wheel.insert(whens[0], 0).unwrap().level, NUM_LEVELS - 1);
+        wheel.insert(whens[1], 1).unwrap();
+
+        assert!(process(&mut wheel, &whens, MAX_DURATION - 1).is_empty());
+        assert_eq!(process(&mut wheel, &whens, MAX_DURATION), [1]);
+        assert!(process(&mut wheel, &whens, MAX_DURATION + 999).is_empty());
+        assert_eq!(process(&mut wheel, &whens, MAX_DURATION + 1_000), [0]);
+    }
+
+    #[test]
+    fn top_level_next_rotation() {
+        // The deadline lands in the slot of the top level `elapsed` is in,
+        // one rotation later
+        let mut wheel = Wheel::new();
+        wheel.set_elapsed(5);
+
+        let when = 5 + MAX_DURATION;
+        let location = wheel.insert(when, 0).unwrap();
+        assert_eq!(location.level, NUM_LEVELS - 1);
+        assert_eq!(location.slot, slot_for(5, NUM_LEVELS - 1));
+
+        assert!(wheel.next_expiration().unwrap().deadline > 5);
+        assert!(process(&mut wheel, &[when], when - 1).is_empty());
+        assert_eq!(process(&mut wheel, &[when], when), [0]);
+    }
+
+    #[test]
+    fn remove() {
+        let mut wheel = Wheel::new();
+        let whens = [100, 100, 5_000];
+
+        let first = wheel.insert(whens[0], 0).unwrap();
+        wheel.insert(whens[1], 1).unwrap();
+        let last = wheel.insert(whens[2], 2).unwrap();
+
+        wheel.remove(first, 0);
+        wheel.remove(last, 2);
+
+        assert_eq!(process(&mut wheel, &whens, 10_000), [1]);
+        assert!(wheel.next_expiration().is_none());
+    }
+
+    #[test]
+    fn remove_clears_slot() {
+        let mut wheel = Wheel::new();
+        let location = wheel.insert(4096, 0).unwrap();
+
+        wheel.remove(location, 0);
+        assert!(wheel.next_expiration().is_none());
+    }
+
+    #[test]
+    fn reset() {
+        // Reset as the time driver does, removing then inserting again
+        let mut wheel = Wheel::new();
+        let mut whens = [300, 200];
+
+        let location = wheel.insert(whens[0], 0).unwrap();
+        wheel.insert(whens[1], 1).unwrap();
+
+        wheel.remove(location, 0);
+        whens[0] = 100;
+        wheel.insert(whens[0], 0).unwrap();
+
+        assert_eq!(process(&mut wheel, &whens, 150), [0]);
+        assert_eq!(process(&mut wheel, &whens, 300), [1]);
+    }
+
+    #[test]
+    fn same_slot_order() {
+        let mut wheel = Wheel::new();
+
+        // Same deadline, fired in insertion order
+        let mut whens = vec![5_000; 3];
+        // Same level 2 slot, fired by deadline once moved down
+        whens.extend([4_200, 4_100]);
+
+        for (key, &when) in whens.iter().enumerate() {
+            wheel.insert(when, key).unwrap();
+        }
+
+        assert_eq!(process(&mut wheel, &whens, 4_200), [4, 3]);
+        assert_eq!(process(&mut wheel, &whens, 5_000), [0, 1, 2]);
+    }
+
+    #[test]
+    fn next_expiration_never_late() {
+        for when in [1, 63, 64, 65, 4_095, 4_096, 4_097, 300_000, MAX_DURATION] {
+            let mut wheel = Wheel::new();
+            wheel.insert(when, 0).unwrap();
+
+            let mut now = 0;
+            while let Some(expiration) = wheel.next_expiration() {
+                assert!(expiration.deadline > now && expiration.deadline <= when);
+                now = expiration.deadline;
+                process(&mut wheel, &[when], now);
+            }
+
+            assert_eq!(now, when);
+        }
+    }
+}
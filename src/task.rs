@@ -1 +1 @@
-pub use crate::runtime::task::JoinHandle;
+pub use crate::runtime::task::{AbortHandle, JoinError, JoinHandle};
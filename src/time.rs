@@ -0,0 +1,36 @@
+//! Utilities for tracking time.
+//!
+//! * `Sleep` is a future that does no work and completes at a specific
+//!   `Instant` in time.
+//!
+//! * `Interval` is a stream yielding a value at a fixed period. It is
+//!   initialized with a `Duration` and repeatedly yields each time the
+//!   duration elapses.
+//!
+//! * `Timeout` wraps a future, setting an upper bound to the amount of time
+//!   it is allowed to execute. If the future does not complete in time, it
+//!   is dropped and an error is returned.
+//!
+//! Timers are driven by the runtime, so they can only be created from within
+//! a runtime context and have a resolution of one millisecond. The clock of
+//! the runtime can be paused with `pause` to test time-based code without
+//! waiting in real time.
+
+mod clock;
+pub use clock::{advance, pause, resume};
+
+pub mod error;
+
+mod instant;
+pub use instant::Instant;
+
+mod interval;
+pub use interval::{interval, interval_at, Interval, MissedTickBehavior};
+
+mod sleep;
+pub use sleep::{sleep, sleep_until, Sleep};
+
+mod timeout;
+pub use timeout::{timeout, timeout_at, Timeout};
+
+pub use std::time::Duration;
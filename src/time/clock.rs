@@ -0,0 +1,74 @@
+use crate::runtime::Handle;
+
+use std::task::Poll;
+use std::time::Duration;
+
+/// Pauses time.
+///
+/// The current value of `Instant::now()` is saved and all subsequent calls
+/// return the saved value. The saved value can be changed by `advance` or by
+/// the time auto-advancing once the runtime has no work to do: when no task
+/// is ready and no I/O event is pending, the clock jumps to the next timer
+/// instead of waiting for it.
+///
+/// This makes tests of time-based code run instantly and deterministically.
+///
+/// # Panics
+///
+/// Panics if time is already frozen or if called from outside of a runtime.
+#[track_caller]
+pub fn pause() {
+    Handle::with_current(|handle| handle.time().clock().pause());
+}
+
+/// Resumes time.
+///
+/// Clears the saved `Instant::now()` value, time then moves forward from
+/// the saved value at the pace of the system clock.
+///
+/// # Panics
+///
+/// Panics if time is not frozen or if called from outside of a runtime.
+#[track_caller]
+pub fn resume() {
+    Handle::with_current(|handle| handle.time().clock().resume());
+}
+
+/// Advances time.
+///
+/// Increments the saved `Instant::now()` value by `duration` and fires the
+/// timers that elapsed. Yields once before, so tasks that were just spawned
+/// or woken register their timers first, and once after, so the tasks
+/// waiting on the fired timers run before the caller resumes.
+///
+/// # Panics
+///
+/// Panics if time is not frozen or if called from outside of a runtime.
+pub async fn advance(duration: Duration) {
+    yield_once().await;
+
+    Handle::with_current(|handle| {
+        let time = handle.time();
+
+        time.clock().advance(duration);
+        time.process(time.clock().now());
+    });
+
+    yield_once().await;
+}
+
+/// Lets the other scheduled tasks run before resuming
+async fn yield_once() {
+    let mut yielded = false;
+
+    crate::future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
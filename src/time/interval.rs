@@ -0,0 +1,139 @@
+use crate::time::{sleep_until, Instant, Sleep};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Creates a new `Interval` that yields with interval of `period`. The first
+/// tick completes immediately.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, or if called from outside of a runtime.
+#[track_caller]
+pub fn interval(period: Duration) -> Interval {
+    interval_at(Instant::now(), period)
+}
+
+/// Creates a new `Interval` that yields with interval of `period` with the
+/// first tick completing at `start`.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, or if called from outside of a runtime.
+#[track_caller]
+pub fn interval_at(start: Instant, period: Duration) -> Interval {
+    assert!(period > Duration::ZERO, "`period` must be non-zero.");
+
+    Interval {
+        delay: sleep_until(start),
+        period,
+        missed_tick_behavior: MissedTickBehavior::default(),
+    }
+}
+
+/// Defines the behavior of an `Interval` when it misses a tick.
+///
+/// A tick is missed when the task awaiting the interval is busy for longer
+/// than the period, e.g. because of a long running computation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedTickBehavior {
+    /// Ticks as fast as possible until caught up, so the same number of
+    /// ticks as scheduled is produced in the long run.
+    ///
+    /// This is the default behavior.
+    #[default]
+    Burst,
+
+    /// Ticks once immediately, then schedules the following ticks `period`
+    /// from then on.
+    Delay,
+
+    /// Skips the missed ticks and ticks on the next multiple of `period`
+    /// from the start.
+    Skip,
+}
+
+impl MissedTickBehavior {
+    /// Deadline of the tick following the missed tick at `timeout`.
+    fn next_timeout(&self, timeout: Instant, now: Instant, period: Duration) -> Instant {
+        match self {
+            MissedTickBehavior::Burst => timeout + period,
+            MissedTickBehavior::Delay => now + period,
+            MissedTickBehavior::Skip => {
+                let behind = (now - timeout).as_nanos() % period.as_nanos();
+                now + period - Duration::from_nanos(behind as u64)
+            }
+        }
+    }
+}
+
+/// Interval returned by `interval` and `interval_at`.
+///
+/// Yields at a fixed period, handling missed ticks according to its
+/// `MissedTickBehavior`.
+#[derive(Debug)]
+pub struct Interval {
+    /// Future that completes on the next tick
+    delay: Sleep,
+
+    period: Duration,
+
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+impl Interval {
+    /// Completes when the next instant in the interval has been reached.
+    ///
+    /// Returns the instant the tick was scheduled for.
+    pub async fn tick(&mut self) -> Instant {
+        crate::future::poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next instant in the interval to be reached.
+    ///
+    /// When `Pending` is returned, the waker of `cx` is notified once the
+    /// tick is reached. Only the waker from the most recent call is
+    /// scheduled to receive a wakeup.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        if Pin::new(&mut self.delay).poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let timeout = self.delay.deadline();
+        let now = Instant::now();
+
+        // Small delays are not considered as missed ticks
+        let next = if now > timeout + Duration::from_millis(5) {
+            self.missed_tick_behavior
+                .next_timeout(timeout, now, self.period)
+        } else {
+            timeout + self.period
+        };
+
+        self.delay.reset(next);
+
+        Poll::Ready(timeout)
+    }
+
+    /// Resets the interval to complete one period after the current time.
+    pub fn reset(&mut self) {
+        self.delay.reset(Instant::now() + self.period);
+    }
+
+    /// Returns the `MissedTickBehavior` strategy currently being used.
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Sets the `MissedTickBehavior` strategy that should be used.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Returns the period of the interval.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+}
@@ -0,0 +1,111 @@
+use crate::runtime::{Handle, WeakHandle};
+use crate::time::Instant;
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Future returned by `sleep` and `sleep_until`.
+///
+/// Completes once the deadline is reached. Polling or resetting it once
+/// the runtime it was created in has been dropped panics.
+pub struct Sleep {
+    /// Runtime the timer is registered with, not kept alive by the timer
+    handle: WeakHandle,
+
+    deadline: Instant,
+
+    /// Key of the timer in the runtime's time handle
+    key: usize,
+}
+
+/// Waits until `duration` has elapsed.
+///
+/// No work is performed while awaiting on the sleep future to complete.
+///
+/// # Panics
+///
+/// Panics if called from outside of a runtime.
+#[track_caller]
+pub fn sleep(duration: Duration) -> Sleep {
+    let deadline = Instant::now()
+        .checked_add(duration)
+        .unwrap_or_else(Instant::far_future);
+
+    sleep_until(deadline)
+}
+
+/// Waits until `deadline` is reached.
+///
+/// # Panics
+///
+/// Panics if called from outside of a runtime.
+#[track_caller]
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Handle::with_current(|handle| Sleep::new(handle, deadline))
+}
+
+impl Sleep {
+    pub(crate) fn new(handle: &Handle, deadline: Instant) -> Sleep {
+        let key = handle.time().register(deadline);
+
+        Sleep {
+            handle: handle.downgrade(),
+            deadline,
+            key,
+        }
+    }
+
+    /// Returns the instant at which the future will complete.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns `true` if `Sleep` has elapsed.
+    pub fn is_elapsed(&self) -> bool {
+        self.handle().time().is_elapsed(self.key)
+    }
+
+    /// Resets the `Sleep` instance to a new deadline.
+    ///
+    /// The future completes once the new deadline is reached, even if it had
+    /// already completed.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.handle().time().reset(self.key, deadline);
+        self.deadline = deadline;
+    }
+
+    #[track_caller]
+    fn handle(&self) -> Handle {
+        self.handle
+            .upgrade()
+            .expect("the runtime the timer was registered with has been dropped")
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.handle().time().poll_elapsed(self.key, cx.waker())
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        // The timer was freed along with the runtime
+        if let Some(handle) = self.handle.upgrade() {
+            handle.time().deregister(self.key);
+        }
+    }
+}
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep")
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
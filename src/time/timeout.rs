@@ -0,0 +1,95 @@
+use crate::time::error::Elapsed;
+use crate::time::{sleep, sleep_until, Instant, Sleep};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Requires a `Future` to complete before the specified duration has
+/// elapsed.
+///
+/// If the future completes before the duration has elapsed, then the
+/// completed value is returned. Otherwise, an error is returned and the
+/// future is dropped.
+///
+/// # Panics
+///
+/// Panics if called from outside of a runtime.
+#[track_caller]
+pub fn timeout<T: Future>(duration: Duration, future: T) -> Timeout<T> {
+    Timeout::new(future, sleep(duration))
+}
+
+/// Requires a `Future` to complete before the specified instant in time.
+///
+/// If the future completes before the instant is reached, then the
+/// completed value is returned. Otherwise, an error is returned and the
+/// future is dropped.
+///
+/// # Panics
+///
+/// Panics if called from outside of a runtime.
+#[track_caller]
+pub fn timeout_at<T: Future>(deadline: Instant, future: T) -> Timeout<T> {
+    Timeout::new(future, sleep_until(deadline))
+}
+
+/// Future returned by `timeout` and `timeout_at`.
+#[derive(Debug)]
+pub struct Timeout<T> {
+    /// Dropped when the deadline is reached
+    value: Option<T>,
+
+    delay: Sleep,
+}
+
+impl<T> Timeout<T> {
+    fn new(value: T, delay: Sleep) -> Timeout<T> {
+        Timeout {
+            value: Some(value),
+            delay,
+        }
+    }
+
+    /// Gets a reference to the underlying value in this timeout, unless the
+    /// deadline was reached.
+    pub fn get_ref(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Consumes this timeout, returning the underlying value, unless the
+    /// deadline was reached.
+    pub fn into_inner(self) -> Option<T> {
+        self.value
+    }
+}
+
+impl<T: Future> Future for Timeout<T> {
+    type Output = Result<T::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: `value` is never moved while pinned, only dropped in place.
+        let this = unsafe { self.get_unchecked_mut() };
+
+        let value = match this.value.as_mut() {
+            Some(value) => unsafe { Pin::new_unchecked(value) },
+            None => panic!("`Timeout` polled after completion"),
+        };
+
+        // First, try polling the future
+        if let Poll::Ready(output) = value.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        // Now check the timer
+        match Pin::new(&mut this.delay).poll(cx) {
+            Poll::Ready(()) => {
+                // Drop the future as soon as the deadline is reached
+                this.value = None;
+                Poll::Ready(Err(Elapsed::new()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use stokio::net::TcpListener;
+use stokio::runtime::Builder;
+use stokio::time::{self, Duration};
+
+use std::cell::Cell;
+use std::future;
+use std::rc::Rc;
+use std::task::Poll;
+
+/// Yield to the scheduler once, waking immediately
+async fn yield_now() {
+    let mut yielded = false;
+
+    future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+#[test]
+fn event_interval_bounds_tasks_polled_per_tick() {
+    let rt = Builder::new().event_interval(3).build().unwrap();
+    let polls = Rc::new(Cell::new(0));
+
+    rt.block_on(async {
+        let counter = polls.clone();
+        drop(stokio::spawn(future::poll_fn(move |cx| {
+            counter.set(counter.get() + 1);
+            cx.waker().wake_by_ref();
+            Poll::<()>::Pending
+        })));
+
+        // The root future runs between ticks, each polling the busy task
+        // up to the interval
+        yield_now().await;
+        let before = polls.get();
+        yield_now().await;
+        assert_eq!(polls.get() - before, 3);
+    });
+}
+
+#[test]
+#[should_panic(expected = "event_interval must be greater than 0")]
+fn zero_event_interval() {
+    Builder::new().event_interval(0);
+}
+
+#[test]
+#[should_panic(expected = "event capacity must be greater than 0")]
+fn zero_event_capacity() {
+    Builder::new().event_capacity(0);
+}
+
+#[test]
+fn capacities_grow_or_batch_as_needed() {
+    let rt = Builder::new()
+        .enable_io()
+        .queue_capacity(1)
+        .event_capacity(1)
+        .resource_capacity(1)
+        .build()
+        .unwrap();
+
+    rt.block_on(async {
+        // More tasks than the initial queue capacity
+        let handles: Vec<_> = (0..16).map(|i| stokio::spawn(async move { i })).collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.await.unwrap(), i);
+        }
+
+        // More resources than the initial slab capacity, all ready at once
+        // but received one event at a time
+        let listeners: Vec<_> = (0..4)
+            .map(|_| {
+                let addr = std::net::TcpListener::bind("127.0.0.1:0")
+                    .unwrap()
+                    .local_addr()
+                    .unwrap();
+                (TcpListener::bind(addr).unwrap(), addr)
+            })
+            .collect();
+
+        let clients: Vec<_> = listeners
+            .iter()
+            .map(|(_, addr)| std::net::TcpStream::connect(addr).unwrap())
+            .collect();
+
+        for (listener, _) in &listeners {
+            listener.accept().await.unwrap();
+        }
+
+        drop(clients);
+    });
+}
+
+/// Name of the current thread as seen by the OS
+#[cfg(target_os = "linux")]
+fn os_thread_name() -> String {
+    let name = std::fs::read_to_string("/proc/thread-self/comm").unwrap();
+    name.trim_end().to_string()
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn thread_named_while_driving_runtime() {
+    let rt = Builder::new().thread_name("stokio-worker").build().unwrap();
+    let prev = os_thread_name();
+
+    rt.block_on(async {
+        assert_eq!(os_thread_name(), "stokio-worker");
+    });
+
+    assert_eq!(os_thread_name(), prev);
+}
+
+#[test]
+#[should_panic(expected = "thread name may not contain nul bytes")]
+fn thread_name_with_nul_byte() {
+    Builder::new().thread_name("stokio\0");
+}
+
+#[test]
+fn thread_start_and_stop_hooks() {
+    let started = Rc::new(Cell::new(0));
+    let stopped = Rc::new(Cell::new(0));
+
+    let rt = {
+        let (started, stopped) = (started.clone(), stopped.clone());
+        Builder::new()
+            .on_thread_start(move || {
+                started.set(started.get() + 1);
+
+                // The runtime is current while the hook runs
+                drop(stokio::spawn(async {}));
+            })
+            .on_thread_stop(move || stopped.set(stopped.get() + 1))
+            .build()
+            .unwrap()
+    };
+
+    rt.block_on(async {
+        assert_eq!(started.get(), 1);
+        assert_eq!(stopped.get(), 0);
+    });
+    assert_eq!(stopped.get(), 1);
+
+    rt.block_on(async {});
+    assert_eq!((started.get(), stopped.get()), (2, 2));
+}
+
+#[test]
+fn drivers_disabled_by_default() {
+    let rt = Builder::new().build().unwrap();
+
+    // Tasks still run without any driver
+    assert_eq!(rt.block_on(rt.spawn(async { 1 })).unwrap(), 1);
+}
+
+#[test]
+#[should_panic(expected = "A runtime with the I/O driver disabled cannot perform I/O")]
+fn io_disabled() {
+    let rt = Builder::new().enable_time().build().unwrap();
+    rt.block_on(async {
+        let _ = TcpListener::bind("127.0.0.1:0".parse().unwrap());
+    });
+}
+
+#[test]
+#[should_panic(expected = "A runtime with the time driver disabled cannot use timers")]
+fn time_disabled() {
+    let rt = Builder::new().enable_io().build().unwrap();
+    rt.block_on(async {
+        time::sleep(Duration::from_millis(1)).await;
+    });
+}
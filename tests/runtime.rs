@@ -0,0 +1,65 @@
+use stokio::runtime::Runtime;
+
+use std::cell::Cell;
+use std::future;
+use std::rc::Rc;
+use std::task::Poll;
+use std::time::Duration;
+
+#[test]
+#[should_panic(expected = "cannot start a runtime from within a runtime")]
+fn nested_block_on() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { rt.block_on(async {}) });
+}
+
+/// Completes after being polled `n` times, waking itself each time
+async fn yield_times(n: usize) {
+    let mut polls = 0;
+    future::poll_fn(|cx| {
+        polls += 1;
+        if polls > n {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+#[test]
+fn run_returns_once_all_tasks_complete() {
+    let rt = Runtime::new().unwrap();
+    let done = Rc::new(Cell::new(0));
+
+    for n in 0..4 {
+        let done = done.clone();
+        rt.spawn(async move {
+            yield_times(n).await;
+
+            // Tasks spawned by tasks are waited for as well
+            let done2 = done.clone();
+            stokio::spawn(async move { done2.set(done2.get() + 1) });
+            done.set(done.get() + 1);
+        });
+    }
+
+    rt.run();
+    assert_eq!(done.get(), 8);
+}
+
+#[test]
+fn shutdown_timeout_cancels_pending_tasks() {
+    let rt = Runtime::new().unwrap();
+    let completed = rt.spawn(async { yield_times(3).await });
+    let pending = rt.spawn(future::pending::<()>());
+
+    rt.shutdown_timeout(Duration::from_millis(20));
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        assert!(completed.await.is_ok());
+        assert!(pending.await.unwrap_err().is_cancelled());
+    });
+}
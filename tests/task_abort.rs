@@ -0,0 +1,92 @@
+use stokio::runtime::Runtime;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Sets the flag when dropped
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+/// Spawn a task that never completes, returning its handle and a flag set
+/// once its future is dropped
+fn spawn_pending() -> (stokio::task::JoinHandle<()>, Rc<Cell<bool>>) {
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    let handle = stokio::spawn(async move {
+        let _flag = flag;
+        std::future::pending::<()>().await
+    });
+
+    (handle, dropped)
+}
+
+/// Let the scheduled tasks run
+async fn run_scheduled() {
+    stokio::spawn(async {}).await.unwrap();
+}
+
+#[test]
+fn abort_pending_task() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (handle, dropped) = spawn_pending();
+        run_scheduled().await;
+
+        // The future is dropped once the task is scheduled again
+        handle.abort();
+        assert!(!dropped.get());
+
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert!(dropped.get());
+    });
+}
+
+#[test]
+fn abort_task_not_yet_polled() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (handle, dropped) = spawn_pending();
+        handle.abort();
+
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert!(dropped.get());
+    });
+}
+
+#[test]
+fn abort_handle_outlives_join_handle() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (handle, dropped) = spawn_pending();
+        run_scheduled().await;
+
+        let abort = handle.abort_handle();
+        let clone = abort.clone();
+        drop(handle);
+        drop(abort);
+
+        clone.abort();
+        run_scheduled().await;
+        assert!(dropped.get());
+    });
+}
+
+#[test]
+fn abort_completed_task_is_noop() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let handle = stokio::spawn(async { 42 });
+        let abort = handle.abort_handle();
+        run_scheduled().await;
+
+        handle.abort();
+        abort.abort();
+        assert_eq!(handle.await.unwrap(), 42);
+    });
+}
@@ -0,0 +1,157 @@
+use stokio::net::TcpListener;
+use stokio::runtime::Runtime;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+/// Counts live allocations made by the current thread, so tests running in
+/// parallel don't observe each other.
+struct LeakCounter;
+
+thread_local!(static LIVE: Cell<isize> = const { Cell::new(0) });
+
+unsafe impl GlobalAlloc for LeakCounter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = LIVE.try_with(|live| live.set(live.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = LIVE.try_with(|live| live.set(live.get() - 1));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LeakCounter = LeakCounter;
+
+fn live_allocations() -> isize {
+    LIVE.with(|live| live.get())
+}
+
+/// Increments the shared counter when dropped
+struct DropCount(Rc<Cell<usize>>);
+
+impl Drop for DropCount {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+/// Never completes and never registers its waker
+struct Forever(#[allow(dead_code)] DropCount);
+
+impl Future for Forever {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+        Poll::Pending
+    }
+}
+
+/// Run a runtime once so lazily initialized thread-locals are allocated
+/// before measuring.
+fn warm_up() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { stokio::spawn(async {}).await.unwrap() });
+}
+
+#[test]
+fn joined_tasks_are_freed() {
+    warm_up();
+    let before = live_allocations();
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        for i in 0..1_000 {
+            let handle = stokio::spawn(async move { vec![i; 16] });
+            assert_eq!(handle.await.unwrap(), vec![i; 16]);
+        }
+    });
+    drop(rt);
+
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn detached_tasks_are_freed() {
+    warm_up();
+    let drops = Rc::new(Cell::new(0));
+    let before = live_allocations();
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        for _ in 0..1_000 {
+            let output = DropCount(drops.clone());
+            drop(stokio::spawn(async move { output }));
+        }
+
+        // Let the detached tasks run
+        stokio::spawn(async {}).await.unwrap();
+    });
+    drop(rt);
+
+    assert_eq!(drops.get(), 1_000);
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn unjoined_output_is_dropped_with_handle() {
+    warm_up();
+    let drops = Rc::new(Cell::new(0));
+    let before = live_allocations();
+
+    let rt = Runtime::new().unwrap();
+    let output = DropCount(drops.clone());
+    let handle = rt.spawn(async move { output });
+    rt.block_on(async { stokio::spawn(async {}).await.unwrap() });
+
+    // The task completed, but the output is kept for the join handle
+    assert_eq!(drops.get(), 0);
+    drop(handle);
+    assert_eq!(drops.get(), 1);
+    drop(rt);
+
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn pending_task_is_freed_on_shutdown() {
+    warm_up();
+    let drops = Rc::new(Cell::new(0));
+    let before = live_allocations();
+
+    let rt = Runtime::new().unwrap();
+    drop(rt.spawn(Forever(DropCount(drops.clone()))));
+    rt.block_on(async { stokio::spawn(async {}).await.unwrap() });
+
+    // The runtime owns the task until it shuts down
+    assert_eq!(drops.get(), 0);
+    drop(rt);
+    assert_eq!(drops.get(), 1);
+
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn io_resources_are_freed() {
+    warm_up();
+    let before = live_allocations();
+
+    let rt = Runtime::new().unwrap();
+    let bind = || TcpListener::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let (pending, outlives) = rt.block_on(async { (bind(), bind()) });
+
+    rt.spawn(async move { pending.accept().await });
+    rt.block_on(async { stokio::spawn(async {}).await.unwrap() });
+
+    // Sockets outliving the runtime don't keep it alive
+    drop(rt);
+    drop(outlives);
+
+    assert_eq!(live_allocations(), before);
+}
@@ -0,0 +1,111 @@
+use stokio::runtime::{Builder, Runtime, UnhandledPanic};
+
+use std::cell::Cell;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+/// Completes right away, but panics once dropped
+struct PanicOnDrop;
+
+impl Future for PanicOnDrop {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<u32> {
+        Poll::Ready(1)
+    }
+}
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("dropped");
+    }
+}
+
+#[test]
+fn panic_dropping_completed_future_fails_task() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let err = stokio::spawn(PanicOnDrop).await.unwrap_err();
+        assert_eq!(*err.into_panic().downcast::<&str>().unwrap(), "dropped");
+
+        // The scheduler keeps running
+        assert_eq!(stokio::spawn(async { 2 }).await.unwrap(), 2);
+    });
+}
+
+#[test]
+fn panic_yields_join_error_with_payload() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let err = stokio::spawn(async { panic!("boom") }).await.unwrap_err();
+        assert!(err.is_panic());
+        assert_eq!(*err.into_panic().downcast::<&str>().unwrap(), "boom");
+
+        let err = stokio::spawn(async { panic::panic_any(7_u32) })
+            .await
+            .unwrap_err();
+        assert_eq!(*err.into_panic().downcast::<u32>().unwrap(), 7);
+    });
+}
+
+#[test]
+fn siblings_keep_running_after_panic() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let ran = Rc::new(Cell::new(0));
+
+        let siblings: Vec<_> = (0..3)
+            .map(|_| {
+                let ran = ran.clone();
+                stokio::spawn(async move {
+                    stokio::spawn(async {}).await.unwrap();
+                    ran.set(ran.get() + 1);
+                })
+            })
+            .collect();
+
+        // Detached, the panic is only reported through the handle
+        drop(stokio::spawn(async { panic!("boom") }));
+
+        for sibling in siblings {
+            sibling.await.unwrap();
+        }
+        assert_eq!(ran.get(), 3);
+    });
+}
+
+#[test]
+fn shutdown_runtime_on_unhandled_panic() {
+    let rt = Builder::new()
+        .unhandled_panic(UnhandledPanic::ShutdownRuntime)
+        .build()
+        .unwrap();
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        rt.block_on(async {
+            drop(stokio::spawn(async { panic!("boom") }));
+            std::future::pending::<()>().await
+        })
+    }));
+    assert!(res.is_err());
+
+    // The runtime stays shut down
+    let res = panic::catch_unwind(AssertUnwindSafe(|| rt.block_on(async {})));
+    assert!(res.is_err());
+}
+
+#[test]
+fn shutdown_runtime_even_if_joined() {
+    let rt = Builder::new()
+        .unhandled_panic(UnhandledPanic::ShutdownRuntime)
+        .build()
+        .unwrap();
+
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        rt.block_on(async { stokio::spawn(async { panic!("boom") }).await })
+    }));
+    assert!(res.is_err());
+}
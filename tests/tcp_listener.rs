@@ -0,0 +1,53 @@
+use stokio::net::TcpListener;
+use stokio::runtime::Runtime;
+
+use std::future;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::thread;
+use std::time::Duration;
+
+/// Returns a loopback address with a port that was free a moment ago
+fn free_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[test]
+fn incoming_accept_loop() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let addr = free_addr();
+        let listener = TcpListener::bind(addr).unwrap();
+
+        // Connect once the loop waits for connections
+        let clients = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            (0..3u8)
+                .map(|i| {
+                    let mut client = std::net::TcpStream::connect(addr).unwrap();
+                    client.write_all(&[i]).unwrap();
+                    client
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut incoming = listener.incoming();
+        let mut received = vec![];
+        while received.len() < 3 {
+            let mut stream = future::poll_fn(|cx| Pin::new(&mut incoming).poll_next(cx))
+                .await
+                .unwrap()
+                .unwrap();
+
+            let mut buf = [0; 1];
+            assert_eq!(stream.read(&mut buf).await.unwrap(), 1);
+            received.push(buf[0]);
+        }
+
+        received.sort();
+        assert_eq!(received, [0, 1, 2]);
+        clients.join().unwrap();
+    });
+}
@@ -0,0 +1,74 @@
+use stokio::net::{TcpSocket, TcpStream};
+use stokio::runtime::Runtime;
+
+use std::future::poll_fn;
+use std::net::Shutdown;
+use std::pin::Pin;
+use tokio::io::AsyncWrite;
+
+/// Read from `stream` until EOF.
+async fn read_to_end(stream: &mut TcpStream) -> Vec<u8> {
+    let mut data = vec![];
+    let mut buf = [0; 64];
+
+    loop {
+        match stream.read(&mut buf).await.unwrap() {
+            0 => return data,
+            n => data.extend_from_slice(&buf[..n]),
+        }
+    }
+}
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let listener = socket.listen(16).unwrap();
+
+    let client = stokio::spawn(async move { TcpStream::connect(addr).await.unwrap() });
+    let (server, _) = listener.accept().await.unwrap();
+
+    (client.await.unwrap(), server)
+}
+
+#[test]
+fn poll_shutdown_sends_eof() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut client, mut server) = pair().await;
+
+        client.write_all(b"request").await.unwrap();
+        poll_fn(|cx| Pin::new(&mut client).poll_shutdown(cx))
+            .await
+            .unwrap();
+
+        // The peer reads the data written before the FIN, then EOF
+        assert_eq!(read_to_end(&mut server).await, b"request");
+
+        // Reading continues on the shut down side
+        server.write_all(b"response").await.unwrap();
+        server.shutdown(Shutdown::Write).unwrap();
+        assert_eq!(read_to_end(&mut client).await, b"response");
+    });
+}
+
+#[test]
+fn dropping_owned_write_half_sends_eof() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (client, mut server) = pair().await;
+        let (mut read, mut write) = client.into_split();
+
+        write.write_all(b"request").await.unwrap();
+        drop(write);
+        assert_eq!(read_to_end(&mut server).await, b"request");
+
+        server.write_all(b"response").await.unwrap();
+        drop(server);
+
+        let mut buf = [0; 64];
+        let n = read.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"response");
+        assert_eq!(read.read(&mut buf).await.unwrap(), 0);
+    });
+}
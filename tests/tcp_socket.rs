@@ -0,0 +1,84 @@
+use stokio::net::TcpSocket;
+use stokio::runtime::Runtime;
+
+use std::io;
+
+#[test]
+fn reuseport() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let a = TcpSocket::new_v4().unwrap();
+        a.set_reuseport(true).unwrap();
+        assert!(a.reuseport().unwrap());
+        a.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = a.local_addr().unwrap();
+        let _a = a.listen(16).unwrap();
+
+        // Both sockets opt in, so they share the port
+        let b = TcpSocket::new_v4().unwrap();
+        b.set_reuseport(true).unwrap();
+        b.bind(addr).unwrap();
+        let _b = b.listen(16).unwrap();
+
+        let c = TcpSocket::new_v4().unwrap();
+        assert!(!c.reuseport().unwrap());
+        let err = c.bind(addr).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+    });
+}
+
+#[test]
+fn listen_queues_backlog() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let socket = TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = socket.local_addr().unwrap();
+
+        let listener = socket.listen(4).unwrap();
+        assert_eq!(listener.local_addr().unwrap(), addr);
+
+        // Connections complete before they are accepted
+        let clients: Vec<_> = (0..3)
+            .map(|_| std::net::TcpStream::connect(addr).unwrap())
+            .collect();
+
+        let mut expected: Vec<_> = clients.iter().map(|c| c.local_addr().unwrap()).collect();
+        let mut accepted = vec![];
+        for _ in 0..3 {
+            let (_, peer) = listener.accept().await.unwrap();
+            accepted.push(peer);
+        }
+
+        expected.sort();
+        accepted.sort();
+        assert_eq!(accepted, expected);
+    });
+}
+
+#[test]
+fn connect() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Options and the local address are set before connecting
+        let socket = TcpSocket::new_v4().unwrap();
+        socket.set_reuseaddr(true).unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let local = socket.local_addr().unwrap();
+
+        let mut stream = socket.connect(addr).await.unwrap();
+        assert_eq!(stream.local_addr().unwrap(), local);
+        assert_eq!(stream.peer_addr().unwrap(), addr);
+
+        let (mut accepted, peer) = listener.accept().unwrap();
+        assert_eq!(peer, local);
+
+        io::Write::write_all(&mut accepted, b"hello").unwrap();
+        let mut buf = [0; 16];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"hello");
+    });
+}
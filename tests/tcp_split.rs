@@ -0,0 +1,99 @@
+use stokio::net::tcp::ReuniteError;
+use stokio::net::{TcpSocket, TcpStream};
+use stokio::runtime::Runtime;
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let listener = socket.listen(16).unwrap();
+
+    let client = stokio::spawn(async move { TcpStream::connect(addr).await.unwrap() });
+    let (server, _) = listener.accept().await.unwrap();
+
+    (client.await.unwrap(), server)
+}
+
+#[test]
+fn owned_halves_in_separate_tasks() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (client, mut server) = pair().await;
+
+        // Enough data to fill the socket buffers in both directions, so the
+        // writer only finishes if the reader runs concurrently
+        let data: Vec<u8> = (0..4 * 1024 * 1024).map(|i| i as u8).collect();
+
+        let echo = stokio::spawn(async move {
+            let mut buf = [0; 4096];
+            loop {
+                match server.read(&mut buf).await.unwrap() {
+                    0 => return,
+                    n => server.write_all(&buf[..n]).await.unwrap(),
+                }
+            }
+        });
+
+        let (mut read, mut write) = client.into_split();
+
+        let writer = stokio::spawn({
+            let data = data.clone();
+            // Dropping the write half sends EOF
+            async move { write.write_all(&data).await.unwrap() }
+        });
+
+        let reader = stokio::spawn(async move {
+            let mut received = vec![];
+            let mut buf = [0; 4096];
+            loop {
+                match read.read(&mut buf).await.unwrap() {
+                    0 => return received,
+                    n => received.extend_from_slice(&buf[..n]),
+                }
+            }
+        });
+
+        writer.await.unwrap();
+        echo.await.unwrap();
+        assert!(reader.await.unwrap() == data);
+    });
+}
+
+#[test]
+fn reunite() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (client, mut server) = pair().await;
+        let addr = client.local_addr().unwrap();
+
+        let (read, write) = client.into_split();
+        let mut client = read.reunite(write).unwrap();
+        assert_eq!(client.local_addr().unwrap(), addr);
+
+        // The reunited stream is still open for writing
+        client.write_all(b"still open").await.unwrap();
+
+        let mut buf = [0; 16];
+        let n = server.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"still open");
+    });
+}
+
+#[test]
+fn reunite_mismatched_halves() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (a, _) = pair().await;
+        let (b, _) = pair().await;
+
+        let (a_read, a_write) = a.into_split();
+        let (b_read, b_write) = b.into_split();
+
+        // The error hands both halves back
+        let ReuniteError(a_read, b_write) = a_read.reunite(b_write).unwrap_err();
+        let ReuniteError(b_read, a_write) = a_write.reunite(b_read).unwrap_err();
+
+        assert!(a_read.reunite(a_write).is_ok());
+        assert!(b_write.reunite(b_read).is_ok());
+    });
+}
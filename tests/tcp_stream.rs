@@ -0,0 +1,382 @@
+use stokio::net::{TcpListener, TcpSocket, TcpStream};
+use stokio::runtime::Runtime;
+
+use std::future;
+use std::io::{self, IoSlice, IoSliceMut, Read, Write};
+use std::mem::MaybeUninit;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Returns a loopback address with a port that was free a moment ago
+fn free_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[test]
+fn connect_round_trip() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        client.write_all(b"request").await.unwrap();
+        let mut buf = [0; 16];
+        server.read_exact(&mut buf[..7]).unwrap();
+        assert_eq!(&buf[..7], b"request");
+
+        server.write_all(b"response").unwrap();
+        let n = client.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"response");
+    });
+}
+
+#[test]
+fn connect_refused() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        // The error is reported through `SO_ERROR` once the socket becomes
+        // writable
+        let err = TcpStream::connect(free_addr()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    });
+}
+
+#[test]
+fn connect_addrs_tries_next_address() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let stream = TcpStream::connect_addrs(&[free_addr(), addr][..])
+            .await
+            .unwrap();
+        let (_, peer) = listener.accept().unwrap();
+        assert_eq!(stream.local_addr().unwrap(), peer);
+
+        let err = TcpStream::connect_addrs(&[free_addr()][..])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    });
+}
+
+#[test]
+fn connect_timeout_connects() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let stream = TcpStream::connect_timeout(addr, Duration::from_secs(5))
+            .await
+            .unwrap();
+        let (_, peer) = listener.accept().unwrap();
+        assert_eq!(stream.local_addr().unwrap(), peer);
+    });
+}
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let listener = socket.listen(16).unwrap();
+
+    let client = stokio::spawn(async move { TcpStream::connect(addr).await.unwrap() });
+    let (server, _) = listener.accept().await.unwrap();
+
+    (client.await.unwrap(), server)
+}
+
+/// Wraps the waker of the polling task, as combinators like `join!` do
+struct Wrapper {
+    inner: Waker,
+    woken: AtomicBool,
+}
+
+impl Wake for Wrapper {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.inner.wake_by_ref();
+    }
+}
+
+#[test]
+fn read_through_wrapping_waker() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let addr = free_addr();
+        let listener = TcpListener::bind(addr).unwrap();
+        let mut client = std::net::TcpStream::connect(addr).unwrap();
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            client.write_all(b"hello").unwrap();
+            client
+        });
+
+        let mut wrapper = None;
+        let mut buf = [0; 16];
+        let n = future::poll_fn(|cx| {
+            let wrapper = wrapper.get_or_insert_with(|| {
+                Arc::new(Wrapper {
+                    inner: cx.waker().clone(),
+                    woken: AtomicBool::new(false),
+                })
+            });
+            let waker = Waker::from(wrapper.clone());
+            let mut buf = ReadBuf::new(&mut buf);
+
+            match Pin::new(&mut stream).poll_read(&mut Context::from_waker(&waker), &mut buf) {
+                Poll::Ready(res) => Poll::Ready(res.map(|()| buf.filled().len())),
+                Poll::Pending => Poll::Pending,
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(&buf[..n], b"hello");
+        assert!(wrapper.unwrap().woken.load(Ordering::SeqCst));
+        writer.join().unwrap();
+    });
+}
+
+#[test]
+fn write_waits_for_peer_to_drain() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        // Fill the send buffer until a write would block
+        let chunk = [0; 64 * 1024];
+        let mut written = 0;
+        loop {
+            match client.try_write(&chunk) {
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) => panic!("{}", e),
+            }
+        }
+
+        // A write now waits for the peer to drain the socket
+        let poll =
+            future::poll_fn(|cx| Poll::Ready(Pin::new(&mut client).poll_write(cx, &chunk))).await;
+        assert!(poll.is_pending());
+
+        let reader = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            let mut buf = Vec::new();
+            server.read_to_end(&mut buf).unwrap();
+            buf.len()
+        });
+
+        let n = client.write(&chunk).await.unwrap();
+        assert!(n > 0);
+        drop(client);
+
+        assert_eq!(reader.join().unwrap(), written + n);
+    });
+}
+
+#[test]
+fn peek_does_not_consume() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut client, mut server) = pair().await;
+
+        // The peek waits for the data
+        let peek = stokio::spawn(async move {
+            let mut buf = [0; 16];
+            let n = server.peek(&mut buf).await.unwrap();
+            (server, buf[..n].to_vec())
+        });
+
+        client.write_all(b"hello").await.unwrap();
+
+        let (mut server, peeked) = peek.await.unwrap();
+        assert_eq!(peeked, b"hello");
+
+        // A short peek keeps the socket readable
+        let mut storage = [MaybeUninit::uninit(); 16];
+        let mut buf = ReadBuf::uninit(&mut storage);
+        let n = future::poll_fn(|cx| server.poll_peek(cx, &mut buf))
+            .await
+            .unwrap();
+        assert_eq!((n, buf.filled()), (5, &b"hello"[..]));
+
+        let mut buf = [0; 16];
+        let n = server.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"hello");
+    });
+}
+
+#[test]
+fn std_round_trip() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut client, server) = pair().await;
+
+        client.write_all(b"to std").await.unwrap();
+
+        let mut std = server.into_std().unwrap();
+        std.set_nonblocking(false).unwrap();
+
+        let mut buf = [0; 6];
+        std.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"to std");
+        std.write_all(b"from std").unwrap();
+
+        let mut buf = [0; 16];
+        let n = client.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"from std");
+
+        // The connection keeps working once registered again
+        let mut server = TcpStream::from_std(std).unwrap();
+
+        let read = stokio::spawn(async move {
+            let mut buf = [0; 16];
+            let n = server.read(&mut buf).await.unwrap();
+            (server, buf[..n].to_vec())
+        });
+
+        client.write_all(b"again").await.unwrap();
+
+        let (mut server, data) = read.await.unwrap();
+        assert_eq!(data, b"again");
+
+        server.write_all(b"bye").await.unwrap();
+        let n = client.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"bye");
+    });
+}
+
+#[test]
+fn write_vectored_read_back() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut client, mut server) = pair().await;
+
+        let bufs = [
+            IoSlice::new(b"head"),
+            IoSlice::new(b""),
+            IoSlice::new(b"body"),
+        ];
+        assert_eq!(client.write_vectored(&bufs).await.unwrap(), 8);
+
+        let mut buf = [0; 16];
+        let n = server.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"headbody");
+
+        // A vectored read fills the buffers in order
+        client.write_all(b"abcdef").await.unwrap();
+        let (mut a, mut b) = ([0; 2], [0; 8]);
+        let n = server
+            .read_vectored(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)])
+            .await
+            .unwrap();
+        assert_eq!((n, &a, &b[..4]), (6, b"ab", &b"cdef"[..]));
+    });
+}
+
+#[test]
+fn try_read_would_block() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (client, mut server) = pair().await;
+
+        let mut buf = [0; 16];
+        let err = server.try_read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+        assert_eq!(client.try_write(b"ready").unwrap(), 5);
+
+        // Wait for the data, then it is read without waiting
+        server.peek(&mut buf).await.unwrap();
+        assert_eq!(server.try_read(&mut buf).unwrap(), 5);
+        assert_eq!(&buf[..5], b"ready");
+
+        let err = server.try_read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    });
+}
+
+#[test]
+fn addresses() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let socket = TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = socket.local_addr().unwrap();
+        let listener = socket.listen(16).unwrap();
+
+        let client = stokio::spawn(async move { TcpStream::connect(addr).await.unwrap() });
+        let (server, peer) = listener.accept().await.unwrap();
+        let client = client.await.unwrap();
+
+        assert_eq!(client.peer_addr().unwrap(), listener.local_addr().unwrap());
+        assert_eq!(server.local_addr().unwrap(), addr);
+        assert_eq!(server.peer_addr().unwrap(), peer);
+        assert_eq!(client.local_addr().unwrap(), peer);
+    });
+}
+
+#[test]
+fn socket_options() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (stream, _server) = pair().await;
+
+        stream.set_nodelay(true).unwrap();
+        assert!(stream.nodelay().unwrap());
+
+        stream.set_ttl(42).unwrap();
+        assert_eq!(stream.ttl().unwrap(), 42);
+
+        stream.set_linger(Some(Duration::from_secs(3))).unwrap();
+        assert_eq!(stream.linger().unwrap(), Some(Duration::from_secs(3)));
+        stream.set_linger(None).unwrap();
+        assert_eq!(stream.linger().unwrap(), None);
+
+        stream.set_keepalive(true).unwrap();
+        assert!(stream.keepalive().unwrap());
+        stream.set_keepalive_idle(Duration::from_secs(30)).unwrap();
+        assert_eq!(stream.keepalive_idle().unwrap(), Duration::from_secs(30));
+        stream
+            .set_keepalive_interval(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(stream.keepalive_interval().unwrap(), Duration::from_secs(5));
+        stream.set_keepalive_retries(4).unwrap();
+        assert_eq!(stream.keepalive_retries().unwrap(), 4);
+
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            stream.set_quickack(true).unwrap();
+            assert!(stream.quickack().unwrap());
+        }
+
+        // The OS may round the buffer sizes up, Linux doubles them
+        stream.set_send_buffer_size(64 * 1024).unwrap();
+        assert!(stream.send_buffer_size().unwrap() >= 64 * 1024);
+        stream.set_recv_buffer_size(64 * 1024).unwrap();
+        assert!(stream.recv_buffer_size().unwrap() >= 64 * 1024);
+    });
+}
@@ -0,0 +1,252 @@
+use stokio::runtime::Runtime;
+use stokio::time::{self, Duration, Instant, MissedTickBehavior};
+
+use std::cell::Cell;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::Poll;
+
+fn rt() -> Runtime {
+    Runtime::new().unwrap()
+}
+
+/// Spawn a task setting the returned flag once `duration` has elapsed
+fn set_after(duration: Duration) -> Rc<Cell<bool>> {
+    let fired = Rc::new(Cell::new(false));
+    let flag = fired.clone();
+
+    stokio::spawn(async move {
+        time::sleep(duration).await;
+        flag.set(true);
+    });
+
+    fired
+}
+
+#[test]
+fn sleep_waits_for_duration() {
+    rt().block_on(async {
+        let start = Instant::now();
+        time::sleep(Duration::from_millis(20)).await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    });
+}
+
+/// Registers a timer with `rt`, to be used once it is no longer running
+fn sleep_on(rt: &Runtime) -> time::Sleep {
+    rt.block_on(std::future::poll_fn(|_| {
+        Poll::Ready(time::sleep(Duration::from_secs(60)))
+    }))
+}
+
+#[test]
+fn sleep_outliving_runtime() {
+    let rt = rt();
+    let sleep = sleep_on(&rt);
+    assert!(!sleep.is_elapsed());
+
+    // The timer does not keep the runtime alive and is freed with it
+    drop(rt);
+    drop(sleep);
+}
+
+#[test]
+#[should_panic(expected = "the runtime the timer was registered with has been dropped")]
+fn sleep_polled_after_runtime_dropped() {
+    let rt = rt();
+    let sleep = sleep_on(&rt);
+    drop(rt);
+
+    self::rt().block_on(sleep);
+}
+
+/// Tick times of an interval of 10ms blocked for 35ms after its first tick,
+/// relative to its start
+fn missed_ticks(behavior: MissedTickBehavior) -> Vec<Duration> {
+    rt().block_on(async {
+        let start = Instant::now();
+
+        let mut interval = time::interval_at(start, Duration::from_millis(10));
+        interval.set_missed_tick_behavior(behavior);
+        assert_eq!(interval.tick().await, start);
+
+        std::thread::sleep(Duration::from_millis(35));
+
+        let mut ticks = vec![];
+        for _ in 0..3 {
+            ticks.push(interval.tick().await - start);
+        }
+        ticks
+    })
+}
+
+fn ms(ms: &[u64]) -> Vec<Duration> {
+    ms.iter().map(|&ms| Duration::from_millis(ms)).collect()
+}
+
+#[test]
+fn interval_ticks_on_schedule() {
+    rt().block_on(async {
+        let start = Instant::now();
+        let mut interval = time::interval_at(start, Duration::from_millis(10));
+
+        for i in 0..5 {
+            let deadline = start + Duration::from_millis(10 * i);
+            assert_eq!(interval.tick().await, deadline);
+            assert!(Instant::now() >= deadline);
+        }
+    });
+}
+
+#[test]
+fn interval_missed_ticks_burst() {
+    assert_eq!(missed_ticks(MissedTickBehavior::Burst), ms(&[10, 20, 30]));
+}
+
+#[test]
+fn interval_missed_ticks_delay() {
+    let ticks = missed_ticks(MissedTickBehavior::Delay);
+
+    // The missed tick fires late, the next ones are a period apart from it
+    assert_eq!(ticks[0], Duration::from_millis(10));
+    assert!(ticks[1] >= Duration::from_millis(35));
+    assert_eq!(ticks[2] - ticks[1], Duration::from_millis(10));
+}
+
+#[test]
+fn interval_missed_ticks_skip() {
+    let ticks = missed_ticks(MissedTickBehavior::Skip);
+
+    // The next ticks stay aligned on the schedule
+    assert_eq!(ticks[0], Duration::from_millis(10));
+    assert!(ticks[1] >= Duration::from_millis(40));
+    assert_eq!(
+        ticks[1].as_nanos() % Duration::from_millis(10).as_nanos(),
+        0
+    );
+    assert_eq!(ticks[2] - ticks[1], Duration::from_millis(10));
+}
+
+/// Sets the flag when dropped
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[test]
+fn timeout_elapses_and_drops_future() {
+    rt().block_on(async {
+        let start = Instant::now();
+
+        let dropped = Rc::new(Cell::new(false));
+        let flag = DropFlag(dropped.clone());
+        let mut timeout = pin!(time::timeout(Duration::from_millis(10), async move {
+            let _flag = flag;
+            std::future::pending::<()>().await
+        }));
+
+        assert!(timeout.as_mut().await.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(10));
+        assert!(dropped.get());
+        assert!(timeout.get_ref().is_none());
+    });
+}
+
+#[test]
+fn timeout_returns_output_of_future() {
+    rt().block_on(async {
+        let output = time::timeout(Duration::from_secs(5), async {
+            time::sleep(Duration::from_millis(10)).await;
+            42
+        })
+        .await;
+
+        assert_eq!(output.unwrap(), 42);
+    });
+}
+
+#[test]
+fn timeout_at_past_deadline_polls_future_once() {
+    rt().block_on(async {
+        let past = Instant::now() - Duration::from_secs(1);
+
+        let output = time::timeout_at(past, async { 42 }).await;
+        assert_eq!(output.unwrap(), 42);
+
+        let polls = Cell::new(0);
+        let output = time::timeout_at(
+            past,
+            std::future::poll_fn(|_| {
+                polls.set(polls.get() + 1);
+                Poll::<()>::Pending
+            }),
+        )
+        .await;
+
+        assert!(output.is_err());
+        assert_eq!(polls.get(), 1);
+    });
+}
+
+#[test]
+fn auto_advance_fires_far_sleep_instantly() {
+    let real = std::time::Instant::now();
+
+    rt().block_on(async {
+        time::pause();
+        let start = Instant::now();
+
+        time::sleep(Duration::from_secs(24 * 3600)).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(24 * 3600));
+    });
+
+    assert!(real.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn advance_fires_only_elapsed_timers() {
+    rt().block_on(async {
+        time::pause();
+
+        let first = set_after(Duration::from_millis(10));
+        let second = set_after(Duration::from_millis(20));
+        let third = set_after(Duration::from_millis(30));
+
+        time::advance(Duration::from_millis(15)).await;
+        assert!(first.get());
+        assert!(!second.get());
+
+        time::advance(Duration::from_millis(5)).await;
+        assert!(second.get());
+        assert!(!third.get());
+    });
+}
+
+#[test]
+fn advance_before_task_registers_timer() {
+    rt().block_on(async {
+        time::pause();
+
+        // The task has not run yet, so its timer is not registered
+        let fired = set_after(Duration::from_millis(100));
+
+        time::advance(Duration::from_millis(100)).await;
+        assert!(fired.get());
+    });
+}
+
+#[test]
+fn resume_follows_system_clock() {
+    rt().block_on(async {
+        time::pause();
+        time::advance(Duration::from_secs(60)).await;
+        time::resume();
+
+        let start = Instant::now();
+        time::sleep(Duration::from_millis(20)).await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    });
+}
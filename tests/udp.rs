@@ -0,0 +1,114 @@
+use stokio::net::UdpSocket;
+use stokio::runtime::Runtime;
+
+use std::future::poll_fn;
+use std::mem::MaybeUninit;
+use tokio::io::ReadBuf;
+
+fn bind() -> UdpSocket {
+    UdpSocket::bind("127.0.0.1:0".parse().unwrap()).unwrap()
+}
+
+#[test]
+fn send_to_recv_from() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let a = bind();
+        let b = bind();
+        let a_addr = a.local_addr().unwrap();
+        let b_addr = b.local_addr().unwrap();
+
+        // The receiver waits for the datagram
+        let recv = stokio::spawn(async move {
+            let mut buf = [0; 16];
+            let (n, from) = b.recv_from(&mut buf).await.unwrap();
+            (buf[..n].to_vec(), from)
+        });
+
+        assert_eq!(a.send_to(b"ping", b_addr).await.unwrap(), 4);
+
+        let (data, from) = recv.await.unwrap();
+        assert_eq!(data, b"ping");
+        assert_eq!(from, a_addr);
+    });
+}
+
+#[test]
+fn connect_send_recv() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let a = bind();
+        let b = bind();
+        let c = bind();
+
+        a.connect(b.local_addr().unwrap()).unwrap();
+        b.connect(a.local_addr().unwrap()).unwrap();
+        assert_eq!(a.peer_addr().unwrap(), b.local_addr().unwrap());
+
+        // Datagrams from other addresses are discarded
+        c.send_to(b"other", a.local_addr().unwrap()).await.unwrap();
+
+        b.send(b"one").await.unwrap();
+        b.send(b"two").await.unwrap();
+
+        let mut buf = [0; 16];
+        let n = a.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"one");
+        let n = a.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"two");
+
+        a.send(b"reply").await.unwrap();
+        let n = b.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"reply");
+    });
+}
+
+#[test]
+fn peek_from_keeps_datagram() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let a = bind();
+        let b = bind();
+        let a_addr = a.local_addr().unwrap();
+
+        a.send_to(b"datagram", b.local_addr().unwrap())
+            .await
+            .unwrap();
+
+        let mut buf = [0; 16];
+        let (n, from) = b.peek_from(&mut buf).await.unwrap();
+        assert_eq!((&buf[..n], from), (&b"datagram"[..], a_addr));
+
+        // Peeking does not remove the datagram from the queue
+        let mut buf = [0; 16];
+        let (n, from) = b.recv_from(&mut buf).await.unwrap();
+        assert_eq!((&buf[..n], from), (&b"datagram"[..], a_addr));
+    });
+}
+
+#[test]
+fn poll_recv_from_uninit_buf() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let a = bind();
+        let b = bind();
+        let a_addr = a.local_addr().unwrap();
+        let b_addr = b.local_addr().unwrap();
+
+        poll_fn(|cx| a.poll_send_to(cx, b"first", b_addr))
+            .await
+            .unwrap();
+        a.send_to(b"second", b_addr).await.unwrap();
+
+        let mut storage = [MaybeUninit::uninit(); 16];
+        let mut buf = ReadBuf::uninit(&mut storage);
+
+        let from = poll_fn(|cx| b.poll_recv_from(cx, &mut buf)).await.unwrap();
+        assert_eq!((buf.filled(), from), (&b"first"[..], a_addr));
+
+        // A second datagram is appended after the first
+        poll_fn(|cx| b.poll_recv_from(cx, &mut buf)).await.unwrap();
+        assert_eq!(buf.filled(), b"firstsecond");
+        assert_eq!(buf.initialized().len(), 11);
+    });
+}
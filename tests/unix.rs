@@ -0,0 +1,132 @@
+use stokio::net::{UnixDatagram, UnixListener, UnixStream};
+use stokio::runtime::Runtime;
+
+use std::path::PathBuf;
+
+/// A socket path unique to this process and `name`, removed if left over.
+fn socket_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("stokio-{}-{}.sock", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn pair() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (mut a, mut b) = UnixStream::pair().unwrap();
+        assert!(a.local_addr().unwrap().is_unnamed());
+
+        a.write_all(b"ping").await.unwrap();
+
+        let mut buf = [0; 16];
+        let n = b.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"ping");
+
+        // Dropping one end reads as EOF on the other
+        drop(b);
+        assert_eq!(a.read(&mut buf).await.unwrap(), 0);
+    });
+}
+
+#[test]
+fn listener_connect() {
+    let path = socket_path("listener");
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = UnixListener::bind(&path).unwrap();
+        assert_eq!(listener.local_addr().unwrap().as_pathname(), Some(&*path));
+
+        let client = stokio::spawn({
+            let path = path.clone();
+            async move {
+                let mut stream = UnixStream::connect(path).await.unwrap();
+                stream.write_all(b"hello").await.unwrap();
+                stream
+            }
+        });
+
+        let (mut server, _) = listener.accept().await.unwrap();
+        let client = client.await.unwrap();
+        assert_eq!(client.peer_addr().unwrap().as_pathname(), Some(&*path));
+
+        let mut buf = [0; 16];
+        let n = server.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"hello");
+    });
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn peer_cred() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let (a, _b) = UnixStream::pair().unwrap();
+        let cred = a.peer_cred().unwrap();
+
+        // The peer is this process
+        assert_eq!(cred.uid(), unsafe { libc::getuid() });
+        assert_eq!(cred.gid(), unsafe { libc::getgid() });
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        assert_eq!(cred.pid(), Some(std::process::id() as libc::pid_t));
+    });
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn abstract_namespace() {
+    let name = format!("stokio-{}-abstract", std::process::id());
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let listener = UnixListener::bind_abstract(&name).unwrap();
+        assert_eq!(
+            listener.local_addr().unwrap().as_abstract_name(),
+            Some(name.as_bytes())
+        );
+
+        let client = stokio::spawn({
+            let name = name.clone();
+            async move { UnixStream::connect_abstract(name).await.unwrap() }
+        });
+
+        let (mut server, _) = listener.accept().await.unwrap();
+        let mut client = client.await.unwrap();
+
+        server.write_all(b"abstract").await.unwrap();
+
+        let mut buf = [0; 16];
+        let n = client.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"abstract");
+    });
+}
+
+#[test]
+fn datagram_send_to_recv_from() {
+    let a_path = socket_path("datagram-a");
+    let b_path = socket_path("datagram-b");
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let a = UnixDatagram::bind(&a_path).unwrap();
+        let b = UnixDatagram::bind(&b_path).unwrap();
+
+        // The receiver waits for the datagram
+        let recv = stokio::spawn(async move {
+            let mut buf = [0; 16];
+            let (n, from) = b.recv_from(&mut buf).await.unwrap();
+            (buf[..n].to_vec(), from)
+        });
+
+        assert_eq!(a.send_to(b"datagram", &b_path).await.unwrap(), 8);
+
+        let (data, from) = recv.await.unwrap();
+        assert_eq!(data, b"datagram");
+        assert_eq!(from.as_pathname(), Some(&*a_path));
+    });
+
+    std::fs::remove_file(&a_path).unwrap();
+    std::fs::remove_file(&b_path).unwrap();
+}
@@ -0,0 +1,169 @@
+use stokio::runtime::{Builder, Runtime};
+
+use std::cell::{Cell, RefCell};
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+/// Completes once `set` is called, possibly from another thread
+#[derive(Clone, Default)]
+struct Flag(Arc<Mutex<(bool, Option<Waker>)>>);
+
+impl Flag {
+    fn set(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.0 = true;
+
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    }
+
+    /// Set the flag from a new thread after `delay`
+    fn set_later(&self, delay: Duration) -> thread::JoinHandle<()> {
+        let flag = self.clone();
+
+        thread::spawn(move || {
+            thread::sleep(delay);
+            flag.set();
+        })
+    }
+}
+
+impl Future for Flag {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.0.lock().unwrap();
+
+        if state.0 {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn root_future_woken_from_other_thread() {
+    let rt = Runtime::new().unwrap();
+    let flag = Flag::default();
+    let thread = flag.set_later(Duration::from_millis(20));
+
+    rt.block_on(flag);
+    thread.join().unwrap();
+}
+
+#[test]
+fn root_future_woken_from_other_thread_without_io() {
+    let rt = Builder::new().build().unwrap();
+    let flag = Flag::default();
+    let thread = flag.set_later(Duration::from_millis(20));
+
+    rt.block_on(flag);
+    thread.join().unwrap();
+}
+
+#[test]
+fn task_woken_from_other_thread() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let flag = Flag::default();
+        let task = stokio::spawn(flag.clone());
+        let thread = flag.set_later(Duration::from_millis(20));
+
+        task.await.unwrap();
+        thread.join().unwrap();
+    });
+}
+
+#[test]
+fn task_woken_from_other_thread_without_io() {
+    let rt = Builder::new().build().unwrap();
+    rt.block_on(async {
+        let flag = Flag::default();
+        let task = stokio::spawn(flag.clone());
+        let thread = flag.set_later(Duration::from_millis(20));
+
+        task.await.unwrap();
+        thread.join().unwrap();
+    });
+}
+
+#[test]
+fn task_woken_several_times_is_queued_once() {
+    let rt = Runtime::new().unwrap();
+    let polls = Rc::new(Cell::new(0));
+    let waker = Rc::new(RefCell::new(None::<Waker>));
+
+    rt.block_on(async {
+        let (polls, waker) = (polls.clone(), waker.clone());
+        drop(stokio::spawn(future::poll_fn(move |cx| {
+            polls.set(polls.get() + 1);
+            *waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::<()>::Pending
+        })));
+
+        // Let the task run and store its waker
+        stokio::spawn(async {}).await.unwrap();
+    });
+
+    rt.block_on(async {
+        let waker = waker.borrow_mut().take().unwrap();
+
+        for _ in 0..3 {
+            waker.wake_by_ref();
+        }
+        waker.wake();
+
+        // Runs after the woken task, which was queued first
+        stokio::spawn(async {}).await.unwrap();
+    });
+
+    assert_eq!(polls.get(), 2);
+}
+
+#[test]
+fn wakers_cloned_and_dropped_on_other_threads() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async {
+        let waker = stokio::spawn(future::poll_fn(|cx| Poll::Ready(cx.waker().clone())))
+            .await
+            .unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let waker = waker.clone();
+                thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        let clone = waker.clone();
+                        waker.wake_by_ref();
+                        clone.wake();
+                    }
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    });
+}
+
+#[test]
+fn waker_outliving_runtime_on_other_thread() {
+    let rt = Runtime::new().unwrap();
+    let waker = rt.block_on(async {
+        stokio::spawn(future::poll_fn(|cx| Poll::Ready(cx.waker().clone())))
+            .await
+            .unwrap()
+    });
+    drop(rt);
+
+    thread::spawn(move || waker.wake()).join().unwrap();
+}